@@ -0,0 +1,59 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use programmer_calc_parser::{Environment, EvalLimits, NumMode, OverflowPolicy, Parser};
+
+// Parses and evaluates generated expressions of growing size, then evaluates them again once
+// compiled. The time per term stays flat when every stage is linear in the length of the input.
+const FLAT_SIZES: [usize; 5] = [1_000, 4_000, 16_000, 64_000, 256_000];
+const NESTED_SIZES: [usize; 3] = [250, 1_000, 4_000];
+// Every nested group is two levels deep, its own and the operand of the addition
+const MAX_DEPTH: usize = 8_192;
+
+fn flat(terms: usize) -> String {
+    let mut input = String::from("1");
+    for index in 1..terms {
+        input.push_str([" + 3", " * 2", " xor 0x5a", " - 1", " lsl 1", " and 0xffff"][index % 6]);
+    }
+    input
+}
+
+fn nested(depth: usize) -> String {
+    format!("{}1{}", "(1 + ".repeat(depth), ")".repeat(depth))
+}
+
+fn time<T>(runs: usize, mut run: impl FnMut() -> T) -> Duration {
+    let start = Instant::now();
+    for _ in 0..runs {
+        black_box(run());
+    }
+    start.elapsed() / runs as u32
+}
+
+fn measure(name: &str, input: &str, terms: usize) {
+    let parser = Parser::new(input.to_string(), NumMode::default().with_overflow_policy(OverflowPolicy::Wrapping)).with_limits(EvalLimits::default().with_max_depth(MAX_DEPTH));
+    let compiled = parser.compile().unwrap();
+    let environment = Environment::new();
+    let runs = (256_000 / terms).max(1);
+
+    let parse = time(runs, || parser.parse().unwrap());
+    let eval = time(runs, || compiled.eval(&environment).unwrap());
+
+    println!(
+        "{:<7} {:>7} terms  parse {:>11.2?} {:>6.1} ns/term  eval {:>11.2?} {:>6.1} ns/term",
+        name, terms, parse, per_term(parse, terms), eval, per_term(eval, terms)
+    );
+}
+
+fn per_term(elapsed: Duration, terms: usize) -> f64 {
+    elapsed.as_nanos() as f64 / terms as f64
+}
+
+fn main() {
+    for terms in FLAT_SIZES {
+        measure("flat", &flat(terms), terms);
+    }
+    for depth in NESTED_SIZES {
+        measure("nested", &nested(depth), depth);
+    }
+}
@@ -0,0 +1,97 @@
+use programmer_calc_parser::{Base, Width};
+
+// Big numbers grow without bound, so that 2 ** 100000000 fails quickly instead of hanging
+pub const DEFAULT_MAX_BITS: u64 = 65_536;
+
+pub const USAGE: &str = "\
+Usage: programmer_calc_parser [options] [expression]...
+
+Evaluates each expression and prints its value. Without expressions or files, reads one
+expression per line from standard input, or starts the prompt when it is a terminal.
+
+Options:
+  -f, --file <path>     Evaluate each line of the file, may be given more than once
+  -b, --base <bases>    Print values in these bases, comma separated:
+                        dec, sdec, udec, hex, oct, bin or all (default dec)
+  -w, --width <bits>    Evaluate with 8, 16, 32, 64 or 128 bits (default 32)
+  -u, --unsigned        Evaluate unsigned numbers
+  -m, --max-bits <bits> Largest number in bits for the big integer format (default 65536)
+  -k, --keep-going      Report every error instead of stopping at the first
+  -j, --json            Print a JSON object for each line instead, errors included
+  -h, --help            Print this help
+
+Blank lines and lines starting with # are skipped, lines starting with : are commands as at
+the prompt. Exits with 1 when an expression fails and 2 when the arguments or a file cannot
+be read.";
+
+// What the command line asks for. Expressions may start with a minus, so only the known flags
+// are read as options, and everything after `--` is an expression.
+#[derive(Debug, Default)]
+pub struct Options {
+    pub expressions: Vec<String>,
+    pub files: Vec<String>,
+    pub bases: Vec<Base>,
+    pub width: Option<Width>,
+    pub unsigned: bool,
+    pub max_bits: Option<u64>,
+    pub keep_going: bool,
+    pub json: bool,
+    pub help: bool
+}
+
+impl Options {
+    // Evaluates what it is given and exits rather than reading from standard input
+    pub fn has_input(&self) -> bool {
+        !self.expressions.is_empty() || !self.files.is_empty()
+    }
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        // --name=value is the same as --name value
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg.clone(), None)
+        };
+
+        match flag.as_str() {
+            "-f" | "--file" => options.files.push(value(inline_value, &mut args, "--file")?),
+            "-b" | "--base" => {
+                for name in value(inline_value, &mut args, "--base")?.split(',') {
+                    match name {
+                        "all" => options.bases.extend(Base::ALL),
+                        _ => options.bases.push(Base::from_name(name).ok_or(format!("Unknown base: {}", name))?)
+                    }
+                }
+            },
+            "-w" | "--width" => {
+                let width = value(inline_value, &mut args, "--width")?.parse::<u32>().ok()
+                    .and_then(Width::from_bits)
+                    .ok_or("Width must be one of 8, 16, 32, 64 or 128")?;
+                options.width = Some(width);
+            },
+            "-u" | "--unsigned" => options.unsigned = true,
+            "-m" | "--max-bits" => {
+                let max_bits = value(inline_value, &mut args, "--max-bits")?.parse::<u64>().ok()
+                    .filter(|bits| *bits > 0)
+                    .ok_or("Maximum bits must be a positive number")?;
+                options.max_bits = Some(max_bits);
+            },
+            "-k" | "--keep-going" => options.keep_going = true,
+            "-j" | "--json" => options.json = true,
+            "-h" | "--help" => options.help = true,
+            "--" => options.expressions.extend(args.by_ref()),
+            _ if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            _ => options.expressions.push(arg)
+        }
+    }
+
+    Ok(options)
+}
+
+fn value(inline_value: Option<String>, args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    inline_value.or_else(|| args.next()).ok_or(format!("Missing value for {}", flag))
+}
@@ -0,0 +1,30 @@
+//! Parser and evaluator for programmer's calculator expressions such as `0xff00 and not 0x0f lsl 4`.
+//!
+//! Integers are written in decimal, with a `0x`, `0b` or `0o` prefix, or with an `h`, `b`, `o` or
+//! `q` suffix after a leading decimal digit, as in `0ffh` or `101b`. Underscores group digits.
+//!
+//! ```
+//! let value = programmer_calc_parser::evaluate("(0xff lsr 4) * 2").unwrap();
+//! assert_eq!(value.as_signed(), 30);
+//! ```
+
+mod parser;
+
+pub use parser::parser::{evaluate, Parser};
+pub use parser::parser::big_int::{BigInt, BigMode};
+pub use parser::parser::compiled::CompiledExpr;
+pub use parser::parser::diagnostic::{DiagnosticRenderer, RenderStyle};
+pub use parser::parser::enums::expr::{BinaryLink, Expr};
+pub use parser::parser::enums::operator::{Operator, UnaryOperator};
+pub use parser::parser::enums::syntax_profile::SyntaxProfile;
+pub use parser::parser::enums::token::Token;
+pub use parser::parser::error::ParseError;
+pub use parser::parser::format::{format_base, Base, BaseFormatter};
+pub use parser::parser::functions::{Arity, Builtin, FunctionError};
+pub use parser::parser::json::{error_json, json_string};
+pub use parser::parser::limits::EvalLimits;
+pub use parser::parser::num_type::{NumFormat, NumMode, NumType, Overflow, OverflowPolicy, SignedNumType, Value, Width};
+pub use parser::parser::numeric::{ArithmeticError, LiteralError, Numeric, Outcome};
+pub use parser::parser::precedence::{Associativity, PrecedenceTable};
+pub use parser::parser::structs::environment::Environment;
+pub use parser::parser::structs::span::Span;
@@ -1,33 +1,414 @@
-mod parser;
+mod cli;
 
-use parser::parser::Parser;
+use std::io::{BufRead, IsTerminal};
+use std::process::ExitCode;
+
+use cli::Options;
+use programmer_calc_parser::{error_json, json_string, Base, BaseFormatter, BigInt, BigMode, DiagnosticRenderer, Environment, EvalLimits, NumMode, Numeric, OverflowPolicy, ParseError, Parser, PrecedenceTable, RenderStyle, Span, SyntaxProfile, Width};
+
+#[derive(Default)]
+struct Session {
+    mode: NumMode,
+    formatter: BaseFormatter,
+    environment: Environment,
+    // Set while evaluating with big integers, which keep their own variables
+    big_mode: Option<BigMode>,
+    big_environment: Environment<BigInt>,
+    syntax: SyntaxProfile,
+    precedence: PrecedenceTable,
+    limits: EvalLimits,
+    // Results are written as JSON, overflows included
+    json: bool
+}
+
+impl Session {
+    // The bases are those of the options when they name any
+    fn new(options: &Options, default_bases: &[Base]) -> Session {
+        let width = options.width.unwrap_or(NumMode::default().width());
+        let bases = if options.bases.is_empty() { default_bases } else { &options.bases };
+
+        Session {
+            mode: NumMode::new(width, !options.unsigned),
+            formatter: BaseFormatter::new(bases.to_vec()),
+            limits: EvalLimits::default().with_max_big_int_bits(options.max_bits.unwrap_or(cli::DEFAULT_MAX_BITS)),
+            json: options.json,
+            ..Session::default()
+        }
+    }
+
+    // The output, and a note when the result overflowed
+    fn evaluate(&mut self, input: &str) -> Result<(String, Option<&'static str>), ParseError> {
+        let (syntax, precedence, limits) = (self.syntax, self.precedence.clone(), self.limits);
+
+        match self.big_mode {
+            Some(mode) => {
+                let parser = Parser::big(input.to_string(), mode).with_syntax(syntax).with_precedence(precedence).with_limits(limits);
+                evaluate(parser, &mut self.big_environment, &self.formatter, self.json)
+            },
+            None => {
+                let parser = Parser::new(input.to_string(), self.mode).with_syntax(syntax).with_precedence(precedence).with_limits(limits);
+                evaluate(parser, &mut self.environment, &self.formatter, self.json)
+            }
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let options = match cli::parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            return ExitCode::from(USAGE_ERROR);
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    if options.has_input() || !std::io::stdin().is_terminal() {
+        return run_batch(&options);
+    }
+
+    let mut session = Session::new(&options, BaseFormatter::default().bases());
+    run_prompt(&mut session);
+
+    ExitCode::SUCCESS
+}
+
+const EVALUATION_ERROR: u8 = 1;
+const USAGE_ERROR: u8 = 2;
+
+// Evaluates the arguments, then the files, or else the lines piped in, all sharing the variables.
+// Values go to the standard output, errors and overflow notes to the standard error, each
+// prefixed with where the line comes from. As JSON, everything goes to the standard output.
+fn run_batch(options: &Options) -> ExitCode {
+    // A single base by default, so that a script reads the value alone
+    let mut batch = Batch {
+        session: Session::new(options, &[Base::Decimal]),
+        renderer: DiagnosticRenderer::new(if std::io::stderr().is_terminal() { RenderStyle::Ansi } else { RenderStyle::Plain }),
+        keep_going: options.keep_going,
+        failed: false
+    };
+
+    for (index, expression) in options.expressions.iter().enumerate() {
+        if !batch.run("<arg>", index + 1, expression) {
+            return batch.exit_code();
+        }
+    }
+
+    for path in &options.files {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                eprintln!("error: cannot read {}: {}", path, err);
+                return ExitCode::from(USAGE_ERROR);
+            }
+        };
+
+        for (index, line) in contents.lines().enumerate() {
+            if !batch.run(path, index + 1, line) {
+                return batch.exit_code();
+            }
+        }
+    }
+
+    if !options.has_input() {
+        for (index, line) in std::io::stdin().lock().lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    eprintln!("error: cannot read the standard input: {}", err);
+                    return ExitCode::from(USAGE_ERROR);
+                }
+            };
+
+            if !batch.run("<stdin>", index + 1, &line) {
+                return batch.exit_code();
+            }
+        }
+    }
+
+    batch.exit_code()
+}
+
+struct Batch {
+    session: Session,
+    renderer: DiagnosticRenderer,
+    keep_going: bool,
+    failed: bool
+}
+
+impl Batch {
+    // Whether to go on with the next line
+    fn run(&mut self, source: &str, line_number: usize, line: &str) -> bool {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return true;
+        }
+
+        // Commands only report their errors, their messages would be mistaken for values
+        if let Some(command) = line.strip_prefix(':') {
+            if let Err(message) = run_command(command, &mut self.session) {
+                match self.session.json {
+                    true => {
+                        let error = error_json("command", &message, Span::new(0, line.len()));
+                        println!("{}", json_record(source, line_number, line, "error", &error));
+                    },
+                    false => eprintln!("{}:{}: error: {}", source, line_number, message)
+                }
+                self.failed = true;
+            }
+            return !self.failed || self.keep_going;
+        }
+
+        match (self.session.evaluate(line), self.session.json) {
+            (Ok((output, _)), true) => println!("{}", json_record(source, line_number, line, "value", &output)),
+            (Ok((output, note)), false) => {
+                println!("{}", output);
+                if let Some(note) = note {
+                    eprintln!("{}:{}: {}", source, line_number, note);
+                }
+            },
+            (Err(err), true) => {
+                println!("{}", json_record(source, line_number, line, "error", &err.to_json()));
+                self.failed = true;
+            },
+            (Err(err), false) => {
+                eprintln!("{}:{}: {}", source, line_number, self.renderer.render(line, &err));
+                self.failed = true;
+            }
+        }
+
+        !self.failed || self.keep_going
+    }
+
+    fn exit_code(&self) -> ExitCode {
+        match self.failed {
+            true => ExitCode::from(EVALUATION_ERROR),
+            false => ExitCode::SUCCESS
+        }
+    }
+}
+
+// {"source":"<arg>","line":1,"input":"1 / 0","error":{...}}, one per line of output
+fn json_record(source: &str, line_number: usize, input: &str, field: &str, json: &str) -> String {
+    format!(r#"{{"source":{},"line":{},"input":{},"{}":{}}}"#, json_string(source), line_number, json_string(input), field, json)
+}
+
+fn run_prompt(session: &mut Session) {
+    let renderer = DiagnosticRenderer::new(if std::io::stdout().is_terminal() { RenderStyle::Ansi } else { RenderStyle::Plain });
 
-fn main() {
-    //println!("{}", "-5".parse::<i32>().unwrap());
     loop {
         print!(">>> ");
         std::io::Write::flush(&mut std::io::stdout()).unwrap();
 
+        // Stops at the end of the input, as when Ctrl-D is pressed
         let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
+        if std::io::stdin().read_line(&mut input).unwrap_or(0) == 0 {
+            println!();
+            break;
+        }
 
         let input = input.trim().to_string();
         if input == "exit" {
             break;
         }
-        if input == "" {
+        if input.is_empty() {
             continue;
         }
 
-        let mut parser = Parser::new(input);
-        let res = match parser.parse() {
-            Ok(res) => res,
-            Err(err) => {
-                println!("{}", err);
-                continue;
+        if let Some(command) = input.strip_prefix(':') {
+            match run_command(command, session) {
+                Ok(message) => println!("{}", message),
+                Err(err) => println!("{}", err)
             }
-        };
+            continue;
+        }
+
+        match session.evaluate(&input) {
+            Ok((output, Some(note))) => println!("{}\n{}", output, note),
+            Ok((output, None)) => println!("{}", output),
+            Err(err) => println!("{}", renderer.render(&input, &err))
+        }
+    }
+}
+
+fn evaluate<N: Numeric>(parser: Parser<N>, environment: &mut Environment<N>, formatter: &BaseFormatter, json: bool) -> Result<(String, Option<&'static str>), ParseError> {
+    let res = parser.parse_with(environment)?;
+    if json {
+        return Ok((res.to_json(), None));
+    }
+
+    let note = match (res.overflowed(), N::overflow_policy(res.mode())) {
+        (true, OverflowPolicy::Saturating) => Some("(overflow, saturated)"),
+        (true, _) => Some("(overflow, wrapped)"),
+        (false, _) => None
+    };
+
+    Ok((formatter.format(&res), note))
+}
+
+// :width <8|16|32|64|128>, :signed, :unsigned, :overflow <error|wrap|saturate>, :mode,
+// :bases <dec|sdec|udec|hex|oct|bin|all>..., :vars, :clear [name]..., :syntax <keywords|c|both>,
+// :precedence <default|c|python>, :carry [0|1], :format <int|float|q1.15|uq8.8|...|big [width]>
+fn run_command(command: &str, session: &mut Session) -> Result<String, String> {
+    let mut args = command.split_whitespace();
+    let mode = session.mode;
+
+    match args.next() {
+        // Big integers take any declared width
+        Some("width") if session.big_mode.is_some() => {
+            let signed = session.big_mode.is_some_and(|big| big.is_signed());
+            let big = args.next()
+                .and_then(|bits| bits.parse::<u32>().ok())
+                .and_then(|bits| BigMode::new(bits, signed))
+                .ok_or("Width must be a positive number of bits")?;
+
+            session.big_mode = Some(big);
+        },
+        Some("width") => {
+            let width = args.next()
+                .and_then(|bits| bits.parse::<u32>().ok())
+                .and_then(Width::from_bits)
+                .ok_or("Width must be one of 8, 16, 32, 64 or 128")?;
+
+            session.mode = NumMode::new(width, mode.is_signed()).with_overflow_policy(mode.overflow_policy());
+        },
+        Some(signedness @ ("signed" | "unsigned")) => {
+            let signed = signedness == "signed";
+
+            match session.big_mode {
+                Some(big) => session.big_mode = BigMode::new(big.width(), signed),
+                None => session.mode = NumMode::new(mode.width(), signed).with_overflow_policy(mode.overflow_policy())
+            }
+        },
+        Some("overflow") if session.big_mode.is_some() => return Err("Big integers never overflow, the overflow policy has no effect".to_string()),
+        Some("overflow") => {
+            let policy = args.next()
+                .and_then(OverflowPolicy::from_name)
+                .ok_or("Overflow policy must be one of error, wrap or saturate")?;
+
+            session.mode = mode.with_overflow_policy(policy);
+        },
+        Some("mode") => {},
+        // Width and signedness apply to the integer format, setting them leaves the others
+        Some("format") => {
+            let format = match args.next() {
+                Some("big") => {
+                    let width = match args.next() {
+                        Some(bits) => bits.parse::<u32>().ok(),
+                        None => Some(BigMode::default().width())
+                    };
+
+                    let big = width
+                        .and_then(|width| BigMode::new(width, mode.is_signed()))
+                        .ok_or("Width must be a positive number of bits")?;
+                    session.big_mode = Some(big);
+                    return Ok(big.to_string());
+                },
+                Some("int") => NumMode::new(mode.width(), mode.is_signed()),
+                Some("float") => NumMode::float(),
+                Some(name) => NumMode::from_q_notation(name).ok_or("Fixed-point formats are written qm.n or uqm.n, at most 64 bits wide")?,
+                None => return Err("Format must be one of int, float, big or a Q format such as q1.15".to_string())
+            };
+
+            session.big_mode = None;
+            session.mode = format.with_overflow_policy(mode.overflow_policy());
+        },
+        Some("bases") => {
+            let mut bases = Vec::new();
+            for arg in args {
+                match arg {
+                    "all" => bases.extend(Base::ALL),
+                    _ => bases.push(Base::from_name(arg).ok_or(format!("Unknown base: {}", arg))?)
+                }
+            }
+
+            if !bases.is_empty() {
+                session.formatter = BaseFormatter::new(bases);
+            }
+
+            let labels: Vec<&str> = session.formatter.bases().iter().map(|base| base.label()).collect();
+            return Ok(labels.join(" "));
+        },
+        Some("syntax") => {
+            if let Some(profile) = args.next() {
+                session.syntax = SyntaxProfile::from_name(profile).ok_or("Syntax must be one of keywords, c or both")?;
+            }
+
+            return Ok(format!("syntax: {}", session.syntax));
+        },
+        Some("precedence") => {
+            if let Some(preset) = args.next() {
+                session.precedence = PrecedenceTable::from_name(preset).ok_or("Precedence must be one of default, c or python")?;
+            }
+
+            return Ok(session.precedence.to_string());
+        },
+        Some("carry") => {
+            let carry = match args.next() {
+                Some("0") => Some(false),
+                Some("1") => Some(true),
+                Some(_) => return Err("Carry must be 0 or 1".to_string()),
+                None => None
+            };
+
+            return Ok(match session.big_mode {
+                Some(_) => carry_command(&mut session.big_environment, carry),
+                None => carry_command(&mut session.environment, carry)
+            });
+        },
+        Some("vars") => {
+            return Ok(match session.big_mode {
+                Some(_) => list_variables(&session.big_environment),
+                None => list_variables(&session.environment)
+            });
+        },
+        Some("clear") => {
+            let names: Vec<&str> = args.collect();
+
+            return match session.big_mode {
+                Some(_) => clear_variables(&mut session.big_environment, &names),
+                None => clear_variables(&mut session.environment, &names)
+            };
+        },
+        _ => return Err(format!("Unknown command: {}", command))
+    }
+
+    match session.big_mode {
+        Some(big) => Ok(big.to_string()),
+        None => Ok(session.mode.to_string())
+    }
+}
+
+fn carry_command<N: Numeric>(environment: &mut Environment<N>, carry: Option<bool>) -> String {
+    if let Some(carry) = carry {
+        environment.set_carry(carry);
+    }
+
+    format!("carry: {}", u8::from(environment.carry()))
+}
+
+fn list_variables<N: Numeric>(environment: &Environment<N>) -> String {
+    let mut lines: Vec<String> = environment.variables()
+        .map(|(name, value)| format!("{} = {}", name, value))
+        .collect();
+    if let Some(value) = environment.last_result() {
+        lines.push(format!("ans = {}", value));
+    }
+
+    if lines.is_empty() { "No variables".to_string() } else { lines.join("\n") }
+}
+
+fn clear_variables<N: Numeric>(environment: &mut Environment<N>, names: &[&str]) -> Result<String, String> {
+    if names.is_empty() {
+        environment.clear();
+        return Ok("Variables cleared".to_string());
+    }
 
-        println!("{}", res);
+    for name in names {
+        environment.remove(name).ok_or(format!("Undefined variable: {}", name))?;
     }
-}
\ No newline at end of file
+    Ok(format!("Removed {}", names.join(" ")))
+}
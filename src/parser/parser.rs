@@ -1,57 +1,117 @@
-use std::collections::VecDeque;
-
-mod enums;
-mod structs;
+pub mod enums;
+pub mod structs;
 mod lexical;
 mod syntax;
 mod semantic;
-mod num_type;
+pub mod num_type;
+pub mod error;
+pub mod diagnostic;
+pub mod format;
+pub mod functions;
+pub mod precedence;
+pub mod numeric;
+pub mod big_int;
+mod native;
+pub mod compiled;
+pub mod limits;
+pub mod json;
 
-use enums::token::Token;
+use big_int::{BigInt, BigMode};
+use compiled::CompiledExpr;
 use lexical::LexicalAnalyzer;
+use enums::token::Token;
+use enums::expr::Expr;
+use enums::syntax_profile::SyntaxProfile;
+use error::ParseError;
+use limits::EvalLimits;
+use num_type::{NumMode, NumType, Value};
+use numeric::Numeric;
+use precedence::PrecedenceTable;
+use structs::environment::Environment;
+use structs::span::Span;
 
+pub struct Parser<N: Numeric = NumType> {
+    operation_string: String,
+    mode: N::Mode,
+    syntax: SyntaxProfile,
+    precedence: PrecedenceTable,
+    limits: EvalLimits
+}
 
+impl Parser {
+    pub fn new(operation_string: String, mode: NumMode) -> Parser {
+        Parser::with_mode(operation_string, mode)
+    }
+}
 
-type NumType = i32; // Auxiliar
-pub struct Parser {
-    operation_string: String
+impl Parser<BigInt> {
+    // Evaluates with arbitrary-precision integers, the mode declaring the width `not` and the
+    // rotations work on
+    pub fn big(operation_string: String, mode: BigMode) -> Parser<BigInt> {
+        Parser::with_mode(operation_string, mode)
+    }
 }
 
-impl Parser {
-    pub fn new(mut operation_string: String) -> Parser {
+impl<N: Numeric> Parser<N> {
+    pub fn with_mode(mut operation_string: String, mode: N::Mode) -> Parser<N> {
         operation_string.make_ascii_lowercase();
 
         Parser {
-            operation_string
+            operation_string,
+            mode,
+            syntax: SyntaxProfile::default(),
+            precedence: PrecedenceTable::default(),
+            limits: EvalLimits::default()
         }
     }
 
-    pub fn parse(&mut self) -> Result<NumType, String> {
-        let lexical_analyzer = LexicalAnalyzer::new(&self.operation_string);
-        let tokens = lexical_analyzer.tokenize()?;
+    pub fn with_syntax(self, syntax: SyntaxProfile) -> Parser<N> {
+        Parser { syntax, ..self }
+    }
+
+    pub fn with_precedence(self, precedence: PrecedenceTable) -> Parser<N> {
+        Parser { precedence, ..self }
+    }
+
+    // Each stage stops at the first limit exceeded, see EvalLimits
+    pub fn with_limits(self, limits: EvalLimits) -> Parser<N> {
+        Parser { limits, ..self }
+    }
+
+    pub fn tokenize(&self) -> Result<Vec<(Token<'_>, Span)>, ParseError> {
+        LexicalAnalyzer::new(&self.operation_string, self.syntax).with_limits(self.limits).tokenize()
+    }
+
+    pub fn analyze(&self) -> Result<Expr<N>, ParseError> {
+        let tokens = self.tokenize()?;
+
+        syntax::SyntaxAnalyzer::new(tokens, self.mode, &self.precedence).with_limits(self.limits).analyze()
+    }
+
+    // Parses once for evaluating many times, see CompiledExpr::eval
+    pub fn compile(&self) -> Result<CompiledExpr<N>, ParseError> {
+        CompiledExpr::new(self.analyze()?, self.mode, self.limits)
+    }
+
+    pub fn parse(&self) -> Result<Value<N>, ParseError> {
+        self.parse_with(&mut Environment::new())
+    }
+
+    // Evaluates against the given variables, storing assignments and the result into them
+    pub fn parse_with(&self, environment: &mut Environment<N>) -> Result<Value<N>, ParseError> {
+        let expression = self.analyze()?;
 
-        let syntax_analyzer = syntax::SyntaxAnalyzer::new(tokens);
-        let operators = syntax_analyzer.analyze()?;
+        let mut semantic_analyzer = semantic::SemanticAnalyzer::new(expression, self.mode).with_limits(self.limits);
+        let result = semantic_analyzer.calculate(environment)?;
 
-        let mut semantic_analyzer = semantic::SemanticAnalyzer::new(operators);
-        let result = semantic_analyzer.calculate();
+        let value = Value::from_raw(result, self.mode, semantic_analyzer.overflowed());
+        environment.set_last_result(value.clone());
 
-        return Ok(result);
+        Ok(value)
     }
 }
 
-enum Operator {
-    Not,
-    Lsl,
-    Lsr,
-    Asl,
-    Asr,
-    Mul,
-    Div,
-    Mod,
-    Add,
-    Sub,
-    And,
-    Xor,
-    Or
+// Evaluates an expression with the default mode: 32-bit signed, erroring on overflow
+pub fn evaluate(operation_string: &str) -> Result<Value, ParseError> {
+    Parser::new(operation_string.to_string(), NumMode::default()).parse()
 }
\ No newline at end of file
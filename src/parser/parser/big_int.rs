@@ -0,0 +1,738 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::enums::operator::Operator;
+use super::num_type::{Overflow, OverflowPolicy, Value};
+use super::numeric::{ArithmeticError, LiteralError, Numeric, Outcome};
+
+const LIMB_BITS: u32 = u32::BITS;
+
+// Arbitrary-precision integer as a sign and a magnitude. The magnitude is in 32-bit limbs from
+// the least significant one, without leading zero limbs, so every number has one representation
+// and zero is never negative.
+#[derive(Debug, PartialEq, Eq, Clone, Default, Hash)]
+pub struct BigInt {
+    negative: bool,
+    magnitude: Vec<u32>
+}
+
+impl BigInt {
+    fn from_parts(negative: bool, mut magnitude: Vec<u32>) -> BigInt {
+        while magnitude.last() == Some(&0) {
+            magnitude.pop();
+        }
+
+        BigInt { negative: negative && !magnitude.is_empty(), magnitude }
+    }
+
+    pub fn zero() -> BigInt {
+        BigInt::default()
+    }
+
+    pub fn power_of_two(exponent: u32) -> BigInt {
+        let mut magnitude = vec![0; (exponent / LIMB_BITS) as usize + 1];
+        magnitude[(exponent / LIMB_BITS) as usize] = 1 << (exponent % LIMB_BITS);
+        BigInt::from_parts(false, magnitude)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.magnitude.is_empty()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    // Bits of the magnitude, zero for zero
+    pub fn bit_length(&self) -> u32 {
+        match self.magnitude.last() {
+            Some(last) => (self.magnitude.len() as u32 - 1) * LIMB_BITS + (LIMB_BITS - last.leading_zeros()),
+            None => 0
+        }
+    }
+
+    // Bit of the magnitude
+    fn bit(&self, index: u32) -> bool {
+        self.magnitude.get((index / LIMB_BITS) as usize).is_some_and(|limb| limb >> (index % LIMB_BITS) & 1 == 1)
+    }
+
+    pub fn to_u32(&self) -> Option<u32> {
+        match (self.negative, &self.magnitude[..]) {
+            (false, []) => Some(0),
+            (false, [limb]) => Some(*limb),
+            _ => None
+        }
+    }
+
+    pub fn abs(&self) -> BigInt {
+        BigInt { negative: false, magnitude: self.magnitude.clone() }
+    }
+
+    pub fn neg(&self) -> BigInt {
+        BigInt::from_parts(!self.negative, self.magnitude.clone())
+    }
+
+    pub fn add(&self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, add_magnitudes(&self.magnitude, &other.magnitude));
+        }
+
+        match compare_magnitudes(&self.magnitude, &other.magnitude) {
+            Ordering::Less => BigInt::from_parts(other.negative, sub_magnitudes(&other.magnitude, &self.magnitude)),
+            _ => BigInt::from_parts(self.negative, sub_magnitudes(&self.magnitude, &other.magnitude))
+        }
+    }
+
+    pub fn sub(&self, other: &BigInt) -> BigInt {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(self.negative != other.negative, mul_magnitudes(&self.magnitude, &other.magnitude))
+    }
+
+    // Truncated toward zero, the remainder taking the sign of the dividend, as for the primitive
+    // integers. The divisor must not be zero.
+    pub fn div_rem(&self, other: &BigInt) -> (BigInt, BigInt) {
+        let (quotient, remainder) = div_rem_magnitudes(&self.magnitude, &other.magnitude);
+        (BigInt::from_parts(self.negative != other.negative, quotient), BigInt::from_parts(self.negative, remainder))
+    }
+
+    pub fn pow(&self, mut exponent: u32) -> BigInt {
+        let mut result = BigInt::from(1u128);
+        let mut base = self.clone();
+
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base);
+            }
+
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.mul(&base);
+            }
+        }
+
+        result
+    }
+
+    pub fn shift_left(&self, amount: u32) -> BigInt {
+        // Zero stays zero however far it is shifted, without building the limbs
+        if self.is_zero() {
+            return BigInt::zero();
+        }
+
+        let limbs = (amount / LIMB_BITS) as usize;
+        let bits = amount % LIMB_BITS;
+
+        let mut magnitude = vec![0; limbs];
+        let mut carry = 0;
+        for limb in &self.magnitude {
+            magnitude.push(limb << bits | carry);
+            carry = if bits == 0 { 0 } else { limb >> (LIMB_BITS - bits) };
+        }
+        magnitude.push(carry);
+
+        BigInt::from_parts(self.negative, magnitude)
+    }
+
+    // Rounds toward negative infinity, as an arithmetic shift of the two's complement would
+    pub fn shift_right(&self, amount: u32) -> BigInt {
+        let shifted = BigInt::from_parts(self.negative, shift_right_magnitude(&self.magnitude, amount));
+
+        let lost_bits = (0..amount.min(self.bit_length())).any(|index| self.bit(index));
+        match self.negative && lost_bits {
+            true => shifted.sub(&BigInt::from(1u128)),
+            false => shifted
+        }
+    }
+
+    pub fn and(&self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |left, right| left & right)
+    }
+
+    pub fn or(&self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |left, right| left | right)
+    }
+
+    pub fn xor(&self, other: &BigInt) -> BigInt {
+        self.bitwise(other, |left, right| left ^ right)
+    }
+
+    // Applied to the two's complement of both numbers, sign-extended as far as needed
+    fn bitwise(&self, other: &BigInt, operation: impl Fn(u32, u32) -> u32) -> BigInt {
+        let length = self.magnitude.len().max(other.magnitude.len()) + 1;
+        let left = self.twos_complement(length);
+        let right = other.twos_complement(length);
+
+        BigInt::from_twos_complement(left.iter().zip(&right).map(|(left, right)| operation(*left, *right)).collect())
+    }
+
+    // The two's complement in `length` limbs, which must be enough to hold the sign bit
+    fn twos_complement(&self, length: usize) -> Vec<u32> {
+        let mut limbs = self.magnitude.clone();
+        limbs.resize(length, 0);
+
+        if self.negative {
+            let mut carry = true;
+            for limb in &mut limbs {
+                (*limb, carry) = (!*limb).overflowing_add(u32::from(carry));
+            }
+        }
+
+        limbs
+    }
+
+    fn from_twos_complement(limbs: Vec<u32>) -> BigInt {
+        if limbs.last().is_none_or(|last| last >> (LIMB_BITS - 1) == 0) {
+            return BigInt::from_parts(false, limbs);
+        }
+
+        let mut carry = true;
+        let magnitude = limbs.iter()
+            .map(|limb| {
+                let (limb, overflowed) = (!limb).overflowing_add(u32::from(carry));
+                carry = overflowed;
+                limb
+            })
+            .collect();
+
+        BigInt::from_parts(true, magnitude)
+    }
+
+    // The low `bits` bits of the two's complement, as a non-negative number
+    pub fn low_bits(&self, bits: u32) -> BigInt {
+        let length = (bits.div_ceil(LIMB_BITS) as usize).max(self.magnitude.len()) + 1;
+        let mut limbs = self.twos_complement(length);
+
+        limbs.truncate(bits.div_ceil(LIMB_BITS) as usize);
+        if let Some(last) = limbs.last_mut().filter(|_| !bits.is_multiple_of(LIMB_BITS)) {
+            *last &= u32::MAX >> (LIMB_BITS - bits % LIMB_BITS);
+        }
+
+        BigInt::from_parts(false, limbs)
+    }
+
+    // Reads a pattern of `bits` bits as a two's complement number when signed
+    pub fn from_bit_pattern(pattern: BigInt, bits: u32, signed: bool) -> BigInt {
+        match signed && bits > 0 && pattern.bit(bits - 1) {
+            true => pattern.sub(&BigInt::power_of_two(bits)),
+            false => pattern
+        }
+    }
+
+    // Digits of the radix, from 2 to 36, without prefix or separators but with a minus sign when
+    // negative. None when a digit is not of the radix or there is none.
+    pub fn from_str_radix(digits: &str, radix: u32) -> Option<BigInt> {
+        let (negative, digits) = match digits.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, digits)
+        };
+        if digits.is_empty() {
+            return None;
+        }
+
+        let mut magnitude = Vec::new();
+        for digit in digits.chars() {
+            mul_add_small(&mut magnitude, radix, digit.to_digit(radix)?);
+        }
+
+        Some(BigInt::from_parts(negative, magnitude))
+    }
+
+    // Digits of the magnitude in the radix, from 2 to 36, with a minus sign when negative
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        // Largest power of the radix fitting in a limb, so each division yields that many digits
+        let mut chunk_digits = 1;
+        let mut chunk = radix;
+        while let Some(next) = chunk.checked_mul(radix) {
+            chunk = next;
+            chunk_digits += 1;
+        }
+
+        let mut chunks = Vec::new();
+        let mut magnitude = self.magnitude.clone();
+        while !magnitude.is_empty() {
+            chunks.push(div_rem_small(&mut magnitude, chunk));
+        }
+
+        let mut digits = String::from(if self.negative { "-" } else { "" });
+        match chunks.pop() {
+            Some(last) => digits.push_str(&to_digits(last, radix, 0)),
+            None => digits.push('0')
+        }
+        for chunk in chunks.iter().rev() {
+            digits.push_str(&to_digits(*chunk, radix, chunk_digits));
+        }
+
+        digits
+    }
+}
+
+impl From<u128> for BigInt {
+    fn from(value: u128) -> BigInt {
+        BigInt::from_parts(false, (0..4).map(|index| (value >> (index * LIMB_BITS)) as u32).collect())
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(value: i128) -> BigInt {
+        let magnitude = BigInt::from(value.unsigned_abs());
+        if value < 0 { magnitude.neg() } else { magnitude }
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => compare_magnitudes(&self.magnitude, &other.magnitude),
+            (true, true) => compare_magnitudes(&other.magnitude, &self.magnitude)
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_str_radix(10))
+    }
+}
+
+// Big integers have no width of their own and never overflow. The declared width is the one
+// `not`, the logical shift right and the rotations work in, their result read back as a signed
+// or unsigned number of that width.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct BigMode {
+    width: u32,
+    signed: bool
+}
+
+impl Default for BigMode {
+    fn default() -> BigMode {
+        BigMode { width: 256, signed: true }
+    }
+}
+
+impl BigMode {
+    // The declared width must not be zero
+    pub fn new(width: u32, signed: bool) -> Option<BigMode> {
+        (width > 0).then_some(BigMode { width, signed })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    // Reads the low bits of a two's complement number in the declared width
+    fn read(&self, pattern: BigInt) -> BigInt {
+        BigInt::from_bit_pattern(pattern.low_bits(self.width), self.width, self.signed)
+    }
+
+    // Amounts are taken modulo the rotated width, so a negative amount rotates the other way
+    fn rotation(&self, value: &BigInt, amount: &BigInt, bits: u32) -> BigInt {
+        let amount = amount.div_rem(&BigInt::from(u128::from(bits))).1;
+        let amount = if amount.is_negative() { amount.add(&BigInt::from(u128::from(bits))) } else { amount };
+        let amount = amount.to_u32().unwrap();
+
+        value.shift_left(amount).or(&value.shift_right(bits - amount)).low_bits(bits)
+    }
+}
+
+impl fmt::Display for BigMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "big integer, declared width {}{}", if self.signed { "i" } else { "u" }, self.width)
+    }
+}
+
+fn amount(amount: &BigInt) -> Result<u32, ArithmeticError<BigInt>> {
+    amount.to_u32().ok_or(ArithmeticError::InvalidShift)
+}
+
+impl Numeric for BigInt {
+    type Mode = BigMode;
+
+    fn parse(digits: &str, radix: u32, _: BigMode) -> Result<BigInt, LiteralError> {
+        BigInt::from_str_radix(digits, radix).ok_or(LiteralError::Invalid)
+    }
+
+    fn convert(value: &Value<BigInt>, _: BigMode) -> Result<BigInt, Overflow<BigInt>> {
+        Ok(value.raw().clone())
+    }
+
+    fn from_bool(value: bool, _: BigMode) -> BigInt {
+        BigInt::from(u128::from(value))
+    }
+
+    fn is_zero(&self, _: BigMode) -> bool {
+        BigInt::is_zero(self)
+    }
+
+    fn compare(&self, other: &BigInt, _: BigMode) -> Ordering {
+        self.cmp(other)
+    }
+
+    fn neg(&self, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::neg(self))
+    }
+
+    fn not(&self, mode: BigMode) -> Outcome<BigInt> {
+        Ok(mode.read(self.xor(&BigInt::from(-1i128))))
+    }
+
+    fn add(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::add(self, other))
+    }
+
+    fn sub(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::sub(self, other))
+    }
+
+    fn mul(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::mul(self, other))
+    }
+
+    fn div(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        match other.is_zero() {
+            true => Err(ArithmeticError::DivisionByZero),
+            false => Ok(self.div_rem(other).0)
+        }
+    }
+
+    fn rem(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        match other.is_zero() {
+            true => Err(ArithmeticError::DivisionByZero),
+            false => Ok(self.div_rem(other).1)
+        }
+    }
+
+    // Exponents past u32::MAX only make sense for the bases whose powers stay small
+    fn pow(&self, exponent: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        if exponent.is_negative() {
+            return Err(ArithmeticError::NegativeExponent);
+        }
+
+        match exponent.to_u32() {
+            Some(exponent) => Ok(BigInt::pow(self, exponent)),
+            None if self.abs() <= BigInt::from(1u128) => {
+                let even = !exponent.bit(0);
+                Ok(if self.is_negative() && even { self.neg() } else { self.clone() })
+            },
+            None => Err(ArithmeticError::Overflow(Overflow { wrapped: BigInt::zero(), positive: !self.is_negative() || !exponent.bit(0) }))
+        }
+    }
+
+    fn and(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::and(self, other))
+    }
+
+    fn or(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::or(self, other))
+    }
+
+    fn xor(&self, other: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(BigInt::xor(self, other))
+    }
+
+    fn lsl(&self, shift: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(self.shift_left(amount(shift)?))
+    }
+
+    // Shifts the pattern of the declared width, so the vacated bits are zeros
+    fn lsr(&self, shift: &BigInt, mode: BigMode) -> Outcome<BigInt> {
+        match amount(shift)? {
+            shift if shift < mode.width => Ok(mode.read(self.low_bits(mode.width).shift_right(shift))),
+            _ => Err(ArithmeticError::InvalidShift)
+        }
+    }
+
+    fn asl(&self, shift: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(self.shift_left(amount(shift)?))
+    }
+
+    fn asr(&self, shift: &BigInt, _: BigMode) -> Outcome<BigInt> {
+        Ok(self.shift_right(amount(shift)?))
+    }
+
+    fn rol(&self, amount: &BigInt, mode: BigMode) -> Outcome<BigInt> {
+        Ok(mode.read(mode.rotation(&self.low_bits(mode.width), amount, mode.width)))
+    }
+
+    fn ror(&self, amount: &BigInt, mode: BigMode) -> Outcome<BigInt> {
+        Ok(mode.read(mode.rotation(&self.low_bits(mode.width), &amount.neg(), mode.width)))
+    }
+
+    // The carry is the most significant bit of a number one bit wider than the declared width
+    fn rcl(&self, amount: &BigInt, carry: bool, mode: BigMode) -> Result<(BigInt, bool), ArithmeticError<BigInt>> {
+        let wide = self.low_bits(mode.width).or(&BigInt::from(u128::from(carry)).shift_left(mode.width));
+        let rotated = mode.rotation(&wide, amount, mode.width + 1);
+
+        Ok((mode.read(rotated.clone()), rotated.bit(mode.width)))
+    }
+
+    fn rcr(&self, amount: &BigInt, carry: bool, mode: BigMode) -> Result<(BigInt, bool), ArithmeticError<BigInt>> {
+        self.rcl(&amount.neg(), carry, mode)
+    }
+
+    fn overflow_policy(_: BigMode) -> OverflowPolicy {
+        OverflowPolicy::Error
+    }
+
+    fn saturated(_: bool, _: BigMode) -> BigInt {
+        unreachable!("big integers only overflow under the error policy")
+    }
+
+    fn format_name(_: BigMode) -> &'static str {
+        "big integer"
+    }
+
+    fn is_signed(mode: BigMode) -> bool {
+        mode.signed
+    }
+
+    fn fmt_value(&self, _: BigMode, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+
+    // The declared width, or as many whole bytes as the two's complement needs beyond it
+    fn bit_pattern(&self, mode: BigMode) -> (BigInt, u32) {
+        let needed = self.bit_length() + u32::from(self.is_negative());
+        let bits = mode.width.max(needed.next_multiple_of(8));
+
+        (self.low_bits(bits), bits)
+    }
+
+    fn magnitude_bits(&self) -> Option<u64> {
+        Some(u64::from(self.bit_length()))
+    }
+
+    // The results of the other operators take at most one more bit than their operands. Shifts
+    // and powers by amounts past u32::MAX fail before growing.
+    fn result_bits(op: Operator, left: &BigInt, right: &BigInt, _: BigMode) -> Option<u64> {
+        let left_bits = u64::from(left.bit_length());
+        let amount = u64::from(right.to_u32()?);
+
+        match op {
+            _ if left.is_zero() => None,
+            Operator::Mul if right.is_zero() => None,
+            Operator::Mul => Some(left_bits + u64::from(right.bit_length()) - 1),
+            Operator::Pow => Some((left_bits - 1) * amount + 1),
+            Operator::Lsl | Operator::Asl => Some(left_bits + amount),
+            _ => None
+        }
+    }
+}
+
+fn to_digits(mut value: u32, radix: u32, width: usize) -> String {
+    let mut digits = Vec::new();
+    while value > 0 || digits.len() < width.max(1) {
+        digits.push(char::from_digit(value % radix, radix).unwrap());
+        value /= radix;
+    }
+
+    digits.iter().rev().collect()
+}
+
+fn compare_magnitudes(left: &[u32], right: &[u32]) -> Ordering {
+    left.len().cmp(&right.len()).then_with(|| left.iter().rev().cmp(right.iter().rev()))
+}
+
+fn add_magnitudes(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let (longer, shorter) = if left.len() >= right.len() { (left, right) } else { (right, left) };
+
+    let mut sum = Vec::with_capacity(longer.len() + 1);
+    let mut carry = 0;
+    for (index, limb) in longer.iter().enumerate() {
+        let total = u64::from(*limb) + u64::from(shorter.get(index).copied().unwrap_or(0)) + carry;
+        sum.push(total as u32);
+        carry = total >> LIMB_BITS;
+    }
+    sum.push(carry as u32);
+
+    sum
+}
+
+// The left magnitude must not be below the right one
+fn sub_magnitudes(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut difference = Vec::with_capacity(left.len());
+    let mut borrow = false;
+    for (index, limb) in left.iter().enumerate() {
+        let (limb, first_borrow) = limb.overflowing_sub(right.get(index).copied().unwrap_or(0));
+        let (limb, second_borrow) = limb.overflowing_sub(u32::from(borrow));
+        difference.push(limb);
+        borrow = first_borrow || second_borrow;
+    }
+
+    difference
+}
+
+fn mul_magnitudes(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; left.len() + right.len()];
+
+    for (i, left_limb) in left.iter().enumerate() {
+        let mut carry = 0;
+        for (j, right_limb) in right.iter().enumerate() {
+            let total = u64::from(*left_limb) * u64::from(*right_limb) + u64::from(product[i + j]) + carry;
+            product[i + j] = total as u32;
+            carry = total >> LIMB_BITS;
+        }
+        product[i + right.len()] = carry as u32;
+    }
+
+    product
+}
+
+fn mul_add_small(magnitude: &mut Vec<u32>, factor: u32, addend: u32) {
+    let mut carry = u64::from(addend);
+    for limb in magnitude.iter_mut() {
+        let total = u64::from(*limb) * u64::from(factor) + carry;
+        *limb = total as u32;
+        carry = total >> LIMB_BITS;
+    }
+
+    if carry > 0 {
+        magnitude.push(carry as u32);
+    }
+}
+
+// Divides in place, returning the remainder, and drops the leading zero limbs of the quotient
+fn div_rem_small(magnitude: &mut Vec<u32>, divisor: u32) -> u32 {
+    let mut remainder = 0u64;
+    for limb in magnitude.iter_mut().rev() {
+        let current = remainder << LIMB_BITS | u64::from(*limb);
+        *limb = (current / u64::from(divisor)) as u32;
+        remainder = current % u64::from(divisor);
+    }
+
+    while magnitude.last() == Some(&0) {
+        magnitude.pop();
+    }
+
+    remainder as u32
+}
+
+// Binary long division, one bit of the quotient at a time. The divisor must not be zero.
+fn div_rem_magnitudes(dividend: &[u32], divisor: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if let [divisor] = divisor {
+        let mut quotient = dividend.to_vec();
+        let remainder = div_rem_small(&mut quotient, *divisor);
+        return (quotient, vec![remainder]);
+    }
+
+    let dividend = BigInt::from_parts(false, dividend.to_vec());
+    let mut quotient = vec![0u32; dividend.magnitude.len()];
+    let mut remainder: Vec<u32> = Vec::new();
+
+    for index in (0..dividend.bit_length()).rev() {
+        remainder = shift_left_one(&remainder, dividend.bit(index));
+        if compare_magnitudes(&remainder, divisor) != Ordering::Less {
+            remainder = sub_magnitudes(&remainder, divisor);
+            while remainder.last() == Some(&0) {
+                remainder.pop();
+            }
+            quotient[(index / LIMB_BITS) as usize] |= 1 << (index % LIMB_BITS);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+fn shift_left_one(magnitude: &[u32], low_bit: bool) -> Vec<u32> {
+    let mut shifted = Vec::with_capacity(magnitude.len() + 1);
+    let mut carry = u32::from(low_bit);
+    for limb in magnitude {
+        shifted.push(limb << 1 | carry);
+        carry = limb >> (LIMB_BITS - 1);
+    }
+    if carry > 0 {
+        shifted.push(carry);
+    }
+
+    shifted
+}
+
+fn shift_right_magnitude(magnitude: &[u32], amount: u32) -> Vec<u32> {
+    let limbs = (amount / LIMB_BITS) as usize;
+    let bits = amount % LIMB_BITS;
+    if limbs >= magnitude.len() {
+        return Vec::new();
+    }
+
+    let kept = &magnitude[limbs..];
+    kept.iter().enumerate()
+        .map(|(index, limb)| {
+            let high = kept.get(index + 1).copied().unwrap_or(0);
+            if bits == 0 { *limb } else { limb >> bits | high << (LIMB_BITS - bits) }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(digits: &str) -> BigInt {
+        match digits.strip_prefix('-') {
+            Some(digits) => BigInt::from_str_radix(digits, 10).unwrap().neg(),
+            None => BigInt::from_str_radix(digits, 10).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let large = big("340282366920938463463374607431768211456");
+
+        assert_eq!(large, BigInt::power_of_two(128));
+        assert_eq!(large.sub(&BigInt::from(1u128)), BigInt::from(u128::MAX));
+        assert_eq!(big("-5").add(&big("3")), big("-2"));
+        assert_eq!(big("5").sub(&big("-3")).to_string(), "8");
+        assert_eq!(large.mul(&large).to_string(), "115792089237316195423570985008687907853269984665640564039457584007913129639936");
+        assert_eq!(big("-2").pow(129), BigInt::power_of_two(129).neg());
+        assert_eq!(big("0").neg(), BigInt::zero());
+    }
+
+    #[test]
+    fn test_division_truncates_toward_zero() {
+        let dividend = BigInt::power_of_two(200).add(&big("12345"));
+        let divisor = BigInt::power_of_two(100).add(&big("1"));
+        let (quotient, remainder) = dividend.div_rem(&divisor);
+
+        assert_eq!(quotient.mul(&divisor).add(&remainder), dividend);
+        assert!(remainder < divisor);
+        assert_eq!(big("-7").div_rem(&big("2")), (big("-3"), big("-1")));
+        assert_eq!(big("7").div_rem(&big("-2")), (big("-3"), big("1")));
+    }
+
+    #[test]
+    fn test_shifts_and_bitwise_use_twos_complement() {
+        assert_eq!(big("1").shift_left(100), BigInt::power_of_two(100));
+        assert_eq!(BigInt::power_of_two(100).shift_right(99), big("2"));
+        assert_eq!(big("-5").shift_right(1), big("-3"));
+        assert_eq!(big("-4").shift_right(1), big("-2"));
+        assert_eq!(big("-1").and(&big("255")), big("255"));
+        assert_eq!(big("-256").or(&big("15")), big("-241"));
+        assert_eq!(big("-1").xor(&BigInt::power_of_two(64)), BigInt::power_of_two(64).add(&big("1")).neg());
+        assert_eq!(big("-1").low_bits(70), BigInt::power_of_two(70).sub(&big("1")));
+        assert_eq!(BigInt::from_bit_pattern(big("255"), 8, true), big("-1"));
+    }
+
+    #[test]
+    fn test_radix_conversions() {
+        assert_eq!(BigInt::from_str_radix("ffffffffffffffffffffffffffffffffff", 16).unwrap().to_str_radix(16), "ffffffffffffffffffffffffffffffffff");
+        assert_eq!(big("-255").to_str_radix(2), "-11111111");
+        assert_eq!(big("1000000000").to_string(), "1000000000");
+        assert_eq!(BigInt::zero().to_str_radix(8), "0");
+        assert_eq!(BigInt::from_str_radix("12", 2), None);
+        assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+    }
+}
@@ -0,0 +1,75 @@
+use super::enums::expr::Expr;
+use super::error::ParseError;
+use super::limits::EvalLimits;
+use super::num_type::{NumType, Value};
+use super::numeric::Numeric;
+use super::semantic::{self, Evaluation, Node, Scope};
+use super::structs::environment::Environment;
+
+// An expression parsed once and evaluated against any number of environments. It owns its tree,
+// so it is Send and Sync whenever the backend is.
+#[derive(Debug, Clone)]
+pub struct CompiledExpr<N: Numeric = NumType> {
+    node: Node<N>,
+    mode: N::Mode,
+    limits: EvalLimits,
+    variables: Vec<String>
+}
+
+impl<N: Numeric> CompiledExpr<N> {
+    // Assignments would need to change the environment, so they are rejected here
+    pub(super) fn new(expression: Expr<N>, mode: N::Mode, limits: EvalLimits) -> Result<CompiledExpr<N>, ParseError> {
+        if let Expr::Assign { name, span, .. } = &expression {
+            return Err(ParseError::CompiledAssignment { name: name.clone(), span: *span });
+        }
+
+        let node = semantic::lower(&expression)?;
+        let mut variables = Vec::new();
+        node.collect_variables(&mut variables);
+
+        Ok(CompiledExpr { node, mode, limits, variables })
+    }
+
+    pub fn mode(&self) -> N::Mode {
+        self.mode
+    }
+
+    // The free variables, in the order they first appear
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    // Leaves the environment untouched. Rotations through the carry start from its carry flag
+    // and pass theirs on to the next rotation of the same evaluation. Every evaluation gets the
+    // whole of the limits of the parser.
+    pub fn eval(&self, environment: &Environment<N>) -> Result<Value<N>, ParseError> {
+        let mut scope = ReadOnlyScope { environment, carry: environment.carry() };
+        let mut evaluation = Evaluation::new(self.limits);
+        let result = semantic::evaluate(&self.node, self.mode, &mut scope, &mut evaluation)?;
+
+        Ok(Value::from_raw(result, self.mode, evaluation.overflowed))
+    }
+}
+
+struct ReadOnlyScope<'a, N: Numeric> {
+    environment: &'a Environment<N>,
+    carry: bool
+}
+
+impl<N: Numeric> Scope<N> for ReadOnlyScope<'_, N> {
+    fn get(&self, name: &str) -> Option<Value<N>> {
+        self.environment.get(name)
+    }
+
+    fn assign(&mut self, _: &str, _: Value<N>) {
+        unreachable!("compiled expressions hold no assignment")
+    }
+
+    fn carry(&self) -> bool {
+        self.carry
+    }
+
+    fn set_carry(&mut self, carry: bool) {
+        self.carry = carry;
+    }
+}
@@ -0,0 +1,200 @@
+use super::enums::operator::Operator;
+use super::error::ParseError;
+use super::functions::Builtin;
+use super::structs::environment::Environment;
+
+const RED: &str = "\x1b[1;31m";
+const CYAN: &str = "\x1b[36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RenderStyle {
+    Plain,
+    Ansi
+}
+
+// Renders an error as the input line with carets under the offending span:
+//
+// error: Unknown word "lsx"
+//   | 1 lsx 2
+//   |   ^^^ did you mean `lsl`?
+pub struct DiagnosticRenderer {
+    style: RenderStyle
+}
+
+impl DiagnosticRenderer {
+    pub fn new(style: RenderStyle) -> DiagnosticRenderer {
+        DiagnosticRenderer { style }
+    }
+
+    pub fn render(&self, input: &str, error: &ParseError) -> String {
+        let span = error.span();
+        let start = floor_char_boundary(input, span.start);
+        let end = floor_char_boundary(input, span.end).max(start);
+
+        let padding = " ".repeat(input[..start].chars().count());
+        let carets = "^".repeat(input[start..end].chars().count().max(1));
+
+        let mut underline = format!("{}{}", padding, self.paint(RED, &carets));
+        if let Some(hint) = hint(error, &input[start..end]) {
+            underline.push(' ');
+            underline.push_str(&self.paint(CYAN, &hint));
+        }
+
+        format!(
+            "{}: {}\n  | {}\n  | {}",
+            self.paint(RED, "error"),
+            self.paint(BOLD, &error.to_string()),
+            input,
+            underline
+        )
+    }
+
+    fn paint(&self, color: &str, text: &str) -> String {
+        match self.style {
+            RenderStyle::Plain => text.to_string(),
+            RenderStyle::Ansi => format!("{}{}{}", color, text, RESET)
+        }
+    }
+}
+
+fn hint(error: &ParseError, text: &str) -> Option<String> {
+    match error {
+        ParseError::UnknownWord { found, .. } => {
+            let keyword = closest(found, &Operator::KEYWORDS)?;
+            Some(format!("did you mean `{}`?", keyword))
+        },
+        ParseError::UnknownFunction { name, .. } => {
+            let names: Vec<&str> = Builtin::ALL.iter().map(|builtin| builtin.name).collect();
+            let function = closest(name, &names)?;
+            Some(format!("did you mean `{}`?", function))
+        },
+        ParseError::UnmatchedParen { .. } if text == "(" => Some("this parenthesis is never closed".to_string()),
+        ParseError::UnmatchedParen { .. } => Some("this parenthesis has no matching `(`".to_string()),
+        ParseError::MissingColon { .. } => Some("this `?` has no matching `:`".to_string()),
+        ParseError::UnexpectedEnd { .. } => Some("expected a number or `(` here".to_string()),
+        ParseError::UndefinedVariable { name, .. } if Environment::LAST_RESULT_NAMES.contains(&name.as_str()) => Some("there is no result yet".to_string()),
+        // Suffixed numbers start with a decimal digit, so ffh is a name
+        ParseError::UndefinedVariable { .. } if is_hex_digits_with_suffix(text) => Some(format!("assign it first, or write `0{}` for a hex number", text)),
+        ParseError::UndefinedVariable { name, .. } => Some(format!("assign it first, as in `{} = 1`", name)),
+        ParseError::InvalidNumber { .. } if text.replace('_', "").parse::<f64>().is_ok() => {
+            Some("fractions need the float or a fixed-point mode, as in `:format float`".to_string())
+        },
+        ParseError::UnsupportedOperator { format: "float", .. } => Some("bitwise operators need an integer or fixed-point mode".to_string()),
+        ParseError::UnexpectedToken { .. } => Some("expected a number or `(` after an operator, and an operator after a number".to_string()),
+        _ => None
+    }
+}
+
+fn is_hex_digits_with_suffix(text: &str) -> bool {
+    text.strip_suffix(['h', 'H'])
+        .is_some_and(|digits| digits.starts_with(|c: char| c.is_ascii_hexdigit()) && digits.chars().all(|c| c.is_ascii_hexdigit() || c == '_'))
+}
+
+fn closest<'a>(word: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let word = word.to_ascii_lowercase();
+
+    candidates.iter()
+        .map(|candidate| (edit_distance(&word, candidate), *candidate))
+        // An exact match is a keyword disabled by the syntax profile, nothing to suggest
+        .filter(|(distance, candidate)| *distance > 0 && *distance <= candidate.len() / 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(left: &str, right: &str) -> usize {
+    let right: Vec<char> = right.chars().collect();
+    let mut previous_row: Vec<usize> = (0..=right.len()).collect();
+
+    for (i, left_char) in left.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+
+        for (j, right_char) in right.iter().enumerate() {
+            let substitution = previous_row[j] + if left_char == *right_char { 0 } else { 1 };
+            current_row.push(substitution.min(previous_row[j + 1] + 1).min(current_row[j] + 1));
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[right.len()]
+}
+
+fn floor_char_boundary(input: &str, index: usize) -> usize {
+    let mut index = index.min(input.len());
+    while !input.is_char_boundary(index) {
+        index -= 1;
+    }
+
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::structs::span::Span;
+
+    #[test]
+    fn test_render_plain_with_hint() {
+        let error = ParseError::UnknownWord { found: "lsx".to_string(), span: Span::new(2, 5) };
+        let rendered = DiagnosticRenderer::new(RenderStyle::Plain).render("1 lsx 2", &error);
+
+        assert_eq!(rendered, "error: Unknown word \"lsx\"\n  | 1 lsx 2\n  |   ^^^ did you mean `lsl`?");
+    }
+
+    #[test]
+    fn test_render_empty_span_at_end() {
+        let error = ParseError::UnexpectedEnd { span: Span::new(3, 3) };
+        let rendered = DiagnosticRenderer::new(RenderStyle::Plain).render("1 +", &error);
+
+        assert_eq!(rendered.lines().last(), Some("  |    ^ expected a number or `(` here"));
+    }
+
+    #[test]
+    fn test_render_unmatched_parenthesis() {
+        let renderer = DiagnosticRenderer::new(RenderStyle::Plain);
+
+        let error = ParseError::UnmatchedParen { span: Span::new(2, 3) };
+        assert_eq!(renderer.render("1*(2", &error).lines().last(), Some("  |   ^ this parenthesis is never closed"));
+
+        let error = ParseError::UnmatchedParen { span: Span::new(1, 2) };
+        assert_eq!(renderer.render("2)", &error).lines().last(), Some("  |  ^ this parenthesis has no matching `(`"));
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let error = ParseError::DivisionByZero { span: Span::new(1, 2) };
+        let rendered = DiagnosticRenderer::new(RenderStyle::Ansi).render("1/0", &error);
+
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+        assert!(rendered.ends_with(" \x1b[1;31m^\x1b[0m"));
+    }
+
+    #[test]
+    fn test_closest_keyword() {
+        assert_eq!(closest("lsx", &Operator::KEYWORDS), Some("lsl"));
+        assert_eq!(closest("xr", &Operator::KEYWORDS), Some("xor"));
+        assert_eq!(closest("hello", &Operator::KEYWORDS), None);
+        assert_eq!(closest("and", &Operator::KEYWORDS), None);
+    }
+
+    #[test]
+    fn test_render_hex_word_with_suffix() {
+        let renderer = DiagnosticRenderer::new(RenderStyle::Plain);
+
+        let error = ParseError::UndefinedVariable { name: "ffh".to_string(), span: Span::new(0, 3) };
+        assert_eq!(renderer.render("FFh", &error).lines().last(), Some("  | ^^^ assign it first, or write `0FFh` for a hex number"));
+
+        let error = ParseError::UndefinedVariable { name: "fg".to_string(), span: Span::new(0, 2) };
+        assert_eq!(renderer.render("fg", &error).lines().last(), Some("  | ^^ assign it first, as in `fg = 1`"));
+    }
+
+    #[test]
+    fn test_render_unknown_function() {
+        let error = ParseError::UnknownFunction { name: "popcnt".to_string(), span: Span::new(0, 6) };
+        let rendered = DiagnosticRenderer::new(RenderStyle::Plain).render("popcnt(3)", &error);
+
+        assert_eq!(rendered.lines().last(), Some("  | ^^^^^^ did you mean `popcount`?"));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use super::operator::{Operator, UnaryOperator};
+use crate::parser::parser::structs::span::Span;
+
+// Syntax tree of an expression. Every node keeps the span of the input it was parsed from.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Expr<NumType> {
+    Literal {
+        value: NumType,
+        span: Span
+    },
+    Unary {
+        op: UnaryOperator,
+        operand: Box<Expr<NumType>>,
+        span: Span
+    },
+    // Operators applied from left to right, each to the value so far and its operand, as in
+    // 1 - 2 * 3 + 4 where the product is an operand. A run of operators is a single node however
+    // long, so the tree is only as deep as the input nests.
+    Binary {
+        first: Box<Expr<NumType>>,
+        links: Vec<BinaryLink<NumType>>,
+        span: Span
+    },
+    Group {
+        inner: Box<Expr<NumType>>,
+        span: Span
+    },
+    // condition ? if_true : if_false, only the chosen branch is evaluated
+    Conditional {
+        condition: Box<Expr<NumType>>,
+        if_true: Box<Expr<NumType>>,
+        if_false: Box<Expr<NumType>>,
+        span: Span
+    },
+    Variable {
+        name: String,
+        span: Span
+    },
+    Call {
+        name: String,
+        name_span: Span,
+        args: Vec<Expr<NumType>>,
+        span: Span
+    },
+    // Only found at the top of the tree, assignments are not expressions themselves
+    Assign {
+        name: String,
+        value: Box<Expr<NumType>>,
+        span: Span
+    }
+}
+
+// An operator of a binary expression with its right operand. The operator span is kept apart to
+// point at it when evaluating the operator fails.
+#[derive(Debug, PartialEq, Clone)]
+pub struct BinaryLink<NumType> {
+    pub op: Operator,
+    pub op_span: Span,
+    pub operand: Expr<NumType>
+}
+
+impl<NumType> Expr<NumType> {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::Literal { span, .. } => *span,
+            Expr::Unary { span, .. } => *span,
+            Expr::Binary { span, .. } => *span,
+            Expr::Group { span, .. } => *span,
+            Expr::Conditional { span, .. } => *span,
+            Expr::Variable { span, .. } => *span,
+            Expr::Call { span, .. } => *span,
+            Expr::Assign { span, .. } => *span
+        }
+    }
+}
+
+// Prints the expression back in the calculator syntax, keeping the original parentheses
+impl<NumType: fmt::Display> fmt::Display for Expr<NumType> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expr::Literal { value, .. } => write!(f, "{}", value),
+            Expr::Unary { op, operand, .. } => write!(f, "{}{}", op, operand),
+            Expr::Binary { first, links, .. } => {
+                write!(f, "{}", first)?;
+                for link in links {
+                    write!(f, " {} {}", link.op, link.operand)?;
+                }
+                Ok(())
+            },
+            Expr::Group { inner, .. } => write!(f, "({})", inner),
+            Expr::Conditional { condition, if_true, if_false, .. } => write!(f, "{} ? {} : {}", condition, if_true, if_false),
+            Expr::Variable { name, .. } => write!(f, "{}", name),
+            Expr::Call { name, args, .. } => {
+                write!(f, "{}(", name)?;
+                for (index, arg) in args.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", arg)?;
+                }
+                write!(f, ")")
+            },
+            Expr::Assign { name, value, .. } => write!(f, "{} = {}", name, value)
+        }
+    }
+}
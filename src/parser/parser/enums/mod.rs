@@ -1,2 +1,3 @@
 pub mod token;
-pub mod element;
\ No newline at end of file
+pub mod operator;
+pub mod expr;pub mod syntax_profile;
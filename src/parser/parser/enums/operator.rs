@@ -0,0 +1,125 @@
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
+    Not,
+    Lsl,
+    Lsr,
+    Asl,
+    Asr,
+    Rol,
+    Ror,
+    Rcl,
+    Rcr,
+    Pow,
+    Mul,
+    Div,
+    Mod,
+    Add,
+    Sub,
+    And,
+    Xor,
+    Or,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    LogicalAnd,
+    LogicalOr
+}
+
+impl Operator {
+    pub const KEYWORDS: [&'static str; 12] = ["not", "lsl", "lsr", "asl", "asr", "rol", "ror", "rcl", "rcr", "and", "xor", "or"];
+    pub const SYMBOLS: [&'static str; 15] = ["**", "+", "-", "*", "/", "%", "==", "!=", "<=", ">=", "<", ">", "&&", "||", "!"];
+    // As in Java, `>>` is the arithmetic shift and `>>>` the logical one
+    pub const C_SYMBOLS: [&'static str; 7] = [">>>", "<<", ">>", "&", "|", "^", "~"];
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(op: &str) -> Option<Operator> {
+        match op {
+            "not" | "~" => Some(Operator::Not),
+            "lsl" | "<<" => Some(Operator::Lsl),
+            "lsr" | ">>>" => Some(Operator::Lsr),
+            "asl" => Some(Operator::Asl),
+            "asr" | ">>" => Some(Operator::Asr),
+            "rol" => Some(Operator::Rol),
+            "ror" => Some(Operator::Ror),
+            "rcl" => Some(Operator::Rcl),
+            "rcr" => Some(Operator::Rcr),
+            "**" => Some(Operator::Pow),
+            "*" => Some(Operator::Mul),
+            "/" => Some(Operator::Div),
+            "%" => Some(Operator::Mod),
+            "+" => Some(Operator::Add),
+            "-" => Some(Operator::Sub),
+            "and" | "&" => Some(Operator::And),
+            "xor" | "^" => Some(Operator::Xor),
+            "or" | "|" => Some(Operator::Or),
+            "==" => Some(Operator::Eq),
+            "!=" => Some(Operator::Ne),
+            "<" => Some(Operator::Lt),
+            "<=" => Some(Operator::Le),
+            ">" => Some(Operator::Gt),
+            ">=" => Some(Operator::Ge),
+            "&&" => Some(Operator::LogicalAnd),
+            "||" => Some(Operator::LogicalOr),
+            _ => None
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Operator::Not => "not",
+            Operator::Lsl => "lsl",
+            Operator::Lsr => "lsr",
+            Operator::Asl => "asl",
+            Operator::Asr => "asr",
+            Operator::Rol => "rol",
+            Operator::Ror => "ror",
+            Operator::Rcl => "rcl",
+            Operator::Rcr => "rcr",
+            Operator::Pow => "**",
+            Operator::Mul => "*",
+            Operator::Div => "/",
+            Operator::Mod => "%",
+            Operator::Add => "+",
+            Operator::Sub => "-",
+            Operator::And => "and",
+            Operator::Xor => "xor",
+            Operator::Or => "or",
+            Operator::Eq => "==",
+            Operator::Ne => "!=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::LogicalAnd => "&&",
+            Operator::LogicalOr => "||"
+        }
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnaryOperator {
+    Neg,
+    Not,
+    LogicalNot
+}
+
+impl fmt::Display for UnaryOperator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnaryOperator::Neg => write!(f, "-"),
+            UnaryOperator::Not => write!(f, "not "),
+            UnaryOperator::LogicalNot => write!(f, "!")
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,52 @@
+use std::fmt;
+
+use super::operator::Operator;
+
+// Which spellings of the operators the lexer accepts. Arithmetic symbols are always accepted.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum SyntaxProfile {
+    Keywords,
+    CLike,
+    #[default]
+    Both
+}
+
+impl SyntaxProfile {
+    pub fn from_name(profile: &str) -> Option<SyntaxProfile> {
+        match profile {
+            "keywords" => Some(SyntaxProfile::Keywords),
+            "c" => Some(SyntaxProfile::CLike),
+            "both" => Some(SyntaxProfile::Both),
+            _ => None
+        }
+    }
+
+    pub fn keywords(&self) -> &'static [&'static str] {
+        match self {
+            SyntaxProfile::CLike => &[],
+            _ => &Operator::KEYWORDS
+        }
+    }
+
+    // Longest first, so the first symbol matching the input is the longest one
+    pub fn symbols(&self) -> Vec<&'static str> {
+        let c_symbols: &'static [&'static str] = match self {
+            SyntaxProfile::Keywords => &[],
+            _ => &Operator::C_SYMBOLS
+        };
+
+        let mut symbols: Vec<&'static str> = c_symbols.iter().chain(Operator::SYMBOLS.iter()).copied().collect();
+        symbols.sort_by_key(|symbol| std::cmp::Reverse(symbol.len()));
+        symbols
+    }
+}
+
+impl fmt::Display for SyntaxProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyntaxProfile::Keywords => write!(f, "keywords"),
+            SyntaxProfile::CLike => write!(f, "c"),
+            SyntaxProfile::Both => write!(f, "both")
+        }
+    }
+}
@@ -1,8 +1,13 @@
 // Tokenize
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum Token<'a> {
-    Number(&'a str),
+    Number(&'a str, u32),
     Operator(&'a str),
+    Identifier(&'a str),
+    Assign,
+    Comma,
+    Question,
+    Colon,
     ParenthesisOpen,
     ParenthesisClose
 }
\ No newline at end of file
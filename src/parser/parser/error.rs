@@ -0,0 +1,137 @@
+use std::fmt;
+
+use super::functions::Arity;
+use super::structs::span::Span;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseError {
+    UnexpectedChar { found: char, span: Span },
+    UnknownWord { found: String, span: Span },
+    UnexpectedToken { found: String, span: Span },
+    UnexpectedEnd { span: Span },
+    UnmatchedParen { span: Span },
+    MissingColon { span: Span },
+    InvalidNumber { span: Span },
+    Overflow { span: Span },
+    DivisionByZero { span: Span },
+    NegativeExponent { span: Span },
+    InvalidShift { span: Span },
+    UndefinedVariable { name: String, span: Span },
+    ReadOnlyVariable { name: String, span: Span },
+    UnknownFunction { name: String, span: Span },
+    ArgumentCount { name: String, expected: Arity, found: usize, span: Span },
+    InvalidArgument { reason: &'static str, span: Span },
+    FractionalExponent { span: Span },
+    UnsupportedOperator { operator: String, format: &'static str, span: Span },
+    UnsupportedFunction { name: String, format: &'static str, span: Span },
+    CompiledAssignment { name: String, span: Span },
+    NestingTooDeep { limit: usize, span: Span },
+    InputTooLong { limit: usize, span: Span },
+    TooManyTokens { limit: usize, span: Span },
+    TooManyOperations { limit: usize, span: Span },
+    NumberTooLarge { limit: u64, span: Span },
+    CallsTooDeep { limit: usize, span: Span }
+}
+
+pub(crate) const NEGATIVE_EXPONENT: &str = "Exponent must not be negative";
+pub(crate) const FRACTIONAL_EXPONENT: &str = "A negative base needs a whole exponent";
+
+impl ParseError {
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedChar { span, .. } => *span,
+            ParseError::UnknownWord { span, .. } => *span,
+            ParseError::UnexpectedToken { span, .. } => *span,
+            ParseError::UnexpectedEnd { span } => *span,
+            ParseError::UnmatchedParen { span } => *span,
+            ParseError::MissingColon { span } => *span,
+            ParseError::InvalidNumber { span } => *span,
+            ParseError::Overflow { span } => *span,
+            ParseError::DivisionByZero { span } => *span,
+            ParseError::NegativeExponent { span } => *span,
+            ParseError::InvalidShift { span } => *span,
+            ParseError::UndefinedVariable { span, .. } => *span,
+            ParseError::ReadOnlyVariable { span, .. } => *span,
+            ParseError::UnknownFunction { span, .. } => *span,
+            ParseError::ArgumentCount { span, .. } => *span,
+            ParseError::InvalidArgument { span, .. } => *span,
+            ParseError::FractionalExponent { span } => *span,
+            ParseError::UnsupportedOperator { span, .. } => *span,
+            ParseError::UnsupportedFunction { span, .. } => *span,
+            ParseError::CompiledAssignment { span, .. } => *span,
+            ParseError::NestingTooDeep { span, .. } => *span,
+            ParseError::InputTooLong { span, .. } => *span,
+            ParseError::TooManyTokens { span, .. } => *span,
+            ParseError::TooManyOperations { span, .. } => *span,
+            ParseError::NumberTooLarge { span, .. } => *span,
+            ParseError::CallsTooDeep { span, .. } => *span
+        }
+    }
+
+    // Names the error for tools, stays the same when the message changes
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedChar { .. } => "unexpected_char",
+            ParseError::UnknownWord { .. } => "unknown_word",
+            ParseError::UnexpectedToken { .. } => "unexpected_token",
+            ParseError::UnexpectedEnd { .. } => "unexpected_end",
+            ParseError::UnmatchedParen { .. } => "unmatched_paren",
+            ParseError::MissingColon { .. } => "missing_colon",
+            ParseError::InvalidNumber { .. } => "invalid_number",
+            ParseError::Overflow { .. } => "overflow",
+            ParseError::DivisionByZero { .. } => "division_by_zero",
+            ParseError::NegativeExponent { .. } => "negative_exponent",
+            ParseError::InvalidShift { .. } => "invalid_shift",
+            ParseError::UndefinedVariable { .. } => "undefined_variable",
+            ParseError::ReadOnlyVariable { .. } => "read_only_variable",
+            ParseError::UnknownFunction { .. } => "unknown_function",
+            ParseError::ArgumentCount { .. } => "argument_count",
+            ParseError::InvalidArgument { .. } => "invalid_argument",
+            ParseError::FractionalExponent { .. } => "fractional_exponent",
+            ParseError::UnsupportedOperator { .. } => "unsupported_operator",
+            ParseError::UnsupportedFunction { .. } => "unsupported_function",
+            ParseError::CompiledAssignment { .. } => "compiled_assignment",
+            ParseError::NestingTooDeep { .. } => "nesting_too_deep",
+            ParseError::InputTooLong { .. } => "input_too_long",
+            ParseError::TooManyTokens { .. } => "too_many_tokens",
+            ParseError::TooManyOperations { .. } => "too_many_operations",
+            ParseError::NumberTooLarge { .. } => "number_too_large",
+            ParseError::CallsTooDeep { .. } => "calls_too_deep"
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedChar { found, .. } => write!(f, r#"Unexpected character "{}""#, found),
+            ParseError::UnknownWord { found, .. } => write!(f, r#"Unknown word "{}""#, found),
+            ParseError::UnexpectedToken { found, .. } => write!(f, r#"Unexpected token "{}""#, found),
+            ParseError::UnexpectedEnd { .. } => write!(f, "Unexpected end of expression"),
+            ParseError::UnmatchedParen { .. } => write!(f, "Unmatched parenthesis"),
+            ParseError::MissingColon { .. } => write!(f, "Conditional is missing its `:` branch"),
+            ParseError::InvalidNumber { .. } => write!(f, "Invalid number"),
+            ParseError::Overflow { .. } => write!(f, "Value does not fit in the current width"),
+            ParseError::DivisionByZero { .. } => write!(f, "Division by zero"),
+            ParseError::NegativeExponent { .. } => write!(f, "{}", NEGATIVE_EXPONENT),
+            ParseError::InvalidShift { .. } => write!(f, "Shift amount must be between 0 and the width minus one"),
+            ParseError::UndefinedVariable { name, .. } => write!(f, r#"Undefined variable "{}""#, name),
+            ParseError::ReadOnlyVariable { name, .. } => write!(f, r#"Cannot assign to "{}", it holds the last result"#, name),
+            ParseError::UnknownFunction { name, .. } => write!(f, r#"Unknown function "{}""#, name),
+            ParseError::ArgumentCount { name, expected, found, .. } => write!(f, "{} takes {}, found {}", name, expected, found),
+            ParseError::InvalidArgument { reason, .. } => write!(f, "{}", reason),
+            ParseError::FractionalExponent { .. } => write!(f, "{}", FRACTIONAL_EXPONENT),
+            ParseError::UnsupportedOperator { operator, format, .. } => write!(f, r#"Operator "{}" is not available in {} mode"#, operator, format),
+            ParseError::UnsupportedFunction { name, format, .. } => write!(f, r#"Function "{}" is not available in {} mode"#, name, format),
+            ParseError::CompiledAssignment { name, .. } => write!(f, r#"Compiled expressions cannot assign to "{}""#, name),
+            ParseError::NestingTooDeep { limit, .. } => write!(f, "Expression nests deeper than {} levels", limit),
+            ParseError::InputTooLong { limit, .. } => write!(f, "Input is longer than {} bytes", limit),
+            ParseError::TooManyTokens { limit, .. } => write!(f, "Input has more than {} tokens", limit),
+            ParseError::TooManyOperations { limit, .. } => write!(f, "Evaluation takes more than {} operations", limit),
+            ParseError::NumberTooLarge { limit, .. } => write!(f, "Number takes more than {} bits", limit),
+            ParseError::CallsTooDeep { limit, .. } => write!(f, "Function calls nest deeper than {} levels", limit)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,134 @@
+use super::big_int::BigInt;
+use super::num_type::Value;
+use super::numeric::Numeric;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Base {
+    Decimal,
+    Signed,
+    Unsigned,
+    Hex,
+    Octal,
+    Binary
+}
+
+impl Base {
+    pub const ALL: [Base; 5] = [Base::Signed, Base::Unsigned, Base::Hex, Base::Octal, Base::Binary];
+
+    pub fn from_name(base: &str) -> Option<Base> {
+        match base {
+            "dec" => Some(Base::Decimal),
+            "sdec" | "signed" => Some(Base::Signed),
+            "udec" | "unsigned" => Some(Base::Unsigned),
+            "hex" => Some(Base::Hex),
+            "oct" => Some(Base::Octal),
+            "bin" => Some(Base::Binary),
+            _ => None
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Base::Decimal => "dec",
+            Base::Signed => "sdec",
+            Base::Unsigned => "udec",
+            Base::Hex => "hex",
+            Base::Octal => "oct",
+            Base::Binary => "bin"
+        }
+    }
+}
+
+// Renders values in one or more bases, padding hex and binary to the width of the value
+pub struct BaseFormatter {
+    bases: Vec<Base>
+}
+
+impl Default for BaseFormatter {
+    fn default() -> BaseFormatter {
+        BaseFormatter::new(vec![Base::Decimal, Base::Hex, Base::Binary])
+    }
+}
+
+impl BaseFormatter {
+    pub fn new(bases: Vec<Base>) -> BaseFormatter {
+        BaseFormatter { bases }
+    }
+
+    pub fn bases(&self) -> &[Base] {
+        &self.bases
+    }
+
+    // A single base is printed bare, several bases one per line with their label
+    pub fn format<N: Numeric>(&self, value: &Value<N>) -> String {
+        if let [base] = self.bases[..] {
+            return format_base(value, base);
+        }
+
+        self.bases.iter()
+            .map(|base| format!("{:<4} {}", base.label(), format_base(value, *base)))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+pub fn format_base<N: Numeric>(value: &Value<N>, base: Base) -> String {
+    let (pattern, bits) = value.raw().bit_pattern(value.mode());
+    let bits = bits as usize;
+
+    match base {
+        Base::Decimal => value.to_string(),
+        Base::Signed => BigInt::from_bit_pattern(pattern.clone(), bits as u32, true).to_string(),
+        Base::Unsigned => pattern.to_string(),
+        Base::Hex => format!("0x{}", group(&format!("{:0>width$}", pattern.to_str_radix(16), width = bits.div_ceil(4)), 4, '_')),
+        Base::Octal => format!("0o{}", pattern.to_str_radix(8)),
+        Base::Binary => group(&format!("{:0>width$}", pattern.to_str_radix(2), width = bits), 4, ' ')
+    }
+}
+
+// Inserts the separator every `size` digits, counting from the least significant digit
+fn group(digits: &str, size: usize, separator: char) -> String {
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / size);
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(size) {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::num_type::{NumMode, Width};
+
+    #[test]
+    fn test_format_bases_honor_width() {
+        let value = Value::new(0xff, NumMode::new(Width::W8, true), false);
+
+        assert_eq!(format_base(&value, Base::Decimal), "-1");
+        assert_eq!(format_base(&value, Base::Signed), "-1");
+        assert_eq!(format_base(&value, Base::Unsigned), "255");
+        assert_eq!(format_base(&value, Base::Hex), "0xff");
+        assert_eq!(format_base(&value, Base::Octal), "0o377");
+        assert_eq!(format_base(&value, Base::Binary), "1111 1111");
+
+        let value = Value::new(0x1f, NumMode::new(Width::W32, false), false);
+        assert_eq!(format_base(&value, Base::Hex), "0x0000_001f");
+        assert_eq!(format_base(&value, Base::Binary), "0000 0000 0000 0000 0000 0000 0001 1111");
+    }
+
+    #[test]
+    fn test_format_multiple_bases() {
+        let value = Value::new(10, NumMode::new(Width::W16, false), false);
+
+        assert_eq!(BaseFormatter::new(vec![Base::Hex]).format(&value), "0x000a");
+        assert_eq!(
+            BaseFormatter::default().format(&value),
+            "dec  10\nhex  0x000a\nbin  0000 0000 0000 1010"
+        );
+    }
+}
\ No newline at end of file
@@ -0,0 +1,294 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::error::{FRACTIONAL_EXPONENT, NEGATIVE_EXPONENT};
+use super::num_type::{NumFormat, NumMode, NumType, Overflow};
+use super::numeric::{ArithmeticError, Numeric};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Arity {
+    Exactly(usize),
+    AtLeast(usize)
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match self {
+            Arity::Exactly(expected) => count == *expected,
+            Arity::AtLeast(expected) => count >= *expected
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arity::Exactly(1) => write!(f, "1 argument"),
+            Arity::Exactly(expected) => write!(f, "{} arguments", expected),
+            Arity::AtLeast(expected) => write!(f, "at least {} arguments", expected)
+        }
+    }
+}
+
+// Failure of a built-in function. Invalid arguments keep the index of the argument at fault.
+// Functions working on the bits of a value are unsupported outside the integer mode.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum FunctionError<N = NumType> {
+    Overflow(Overflow<N>),
+    InvalidArgument { index: usize, reason: &'static str },
+    Unsupported
+}
+
+type Apply = fn(&[NumType], NumMode) -> Result<NumType, FunctionError>;
+
+// A function callable as `name(arg, ...)`. The arguments are already truncated to the active
+// width, and are checked against the arity before the function is applied.
+#[derive(Debug)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub arity: Arity,
+    apply: Apply
+}
+
+impl Builtin {
+    // The most arguments of a function taking a fixed number of them
+    pub const MAX_FIXED_ARGS: usize = 2;
+
+    pub const ALL: [Builtin; 14] = [
+        Builtin { name: "popcount", arity: Arity::Exactly(1), apply: popcount },
+        Builtin { name: "clz", arity: Arity::Exactly(1), apply: clz },
+        Builtin { name: "ctz", arity: Arity::Exactly(1), apply: ctz },
+        Builtin { name: "bswap", arity: Arity::Exactly(1), apply: bswap },
+        Builtin { name: "rotl", arity: Arity::Exactly(2), apply: rotl },
+        Builtin { name: "rotr", arity: Arity::Exactly(2), apply: rotr },
+        Builtin { name: "bit", arity: Arity::Exactly(1), apply: bit },
+        Builtin { name: "mask", arity: Arity::Exactly(2), apply: mask },
+        Builtin { name: "min", arity: Arity::AtLeast(2), apply: min },
+        Builtin { name: "max", arity: Arity::AtLeast(2), apply: max },
+        Builtin { name: "abs", arity: Arity::Exactly(1), apply: abs },
+        Builtin { name: "pow", arity: Arity::Exactly(2), apply: pow },
+        Builtin { name: "sext", arity: Arity::Exactly(2), apply: sext },
+        Builtin { name: "sign_extend", arity: Arity::Exactly(2), apply: sext }
+    ];
+
+    pub fn find(name: &str) -> Option<&'static Builtin> {
+        const ALL: &[Builtin] = &Builtin::ALL;
+        ALL.iter().find(|builtin| builtin.name == name)
+    }
+
+    pub fn apply(&self, args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+        (self.apply)(args, mode)
+    }
+
+    // min, max, abs and pow through the operators of any backend, the others are unsupported
+    pub fn apply_numeric<N: Numeric>(&self, args: &[N], mode: N::Mode) -> Result<N, FunctionError<N>> {
+        match self.name {
+            "min" => Ok(args.iter().min_by(|left, right| left.compare(right, mode)).unwrap().clone()),
+            "max" => Ok(args.iter().max_by(|left, right| left.compare(right, mode)).unwrap().clone()),
+            "abs" => match args[0].compare(&N::from_bool(false, mode), mode) {
+                Ordering::Less => args[0].neg(mode).map_err(to_function_error),
+                _ => Ok(args[0].clone())
+            },
+            "pow" => args[0].pow(&args[1], mode).map_err(to_function_error),
+            _ => Err(FunctionError::Unsupported)
+        }
+    }
+}
+
+// Only the exponent of pow can be at fault
+fn to_function_error<N>(error: ArithmeticError<N>) -> FunctionError<N> {
+    match error {
+        ArithmeticError::Overflow(overflow) => FunctionError::Overflow(overflow),
+        ArithmeticError::NegativeExponent => FunctionError::InvalidArgument { index: 1, reason: NEGATIVE_EXPONENT },
+        ArithmeticError::FractionalExponent => FunctionError::InvalidArgument { index: 1, reason: FRACTIONAL_EXPONENT },
+        _ => FunctionError::Unsupported
+    }
+}
+
+const INVALID_BIT_INDEX: &str = "Bit index must be between 0 and the width minus one";
+
+fn require_integer(mode: NumMode) -> Result<(), FunctionError> {
+    match mode.format() {
+        NumFormat::Integer => Ok(()),
+        _ => Err(FunctionError::Unsupported)
+    }
+}
+
+fn bit_index(args: &[NumType], index: usize, mode: NumMode) -> Result<u32, FunctionError> {
+    match args[index] < mode.bits() as NumType {
+        true => Ok(args[index] as u32),
+        false => Err(FunctionError::InvalidArgument { index, reason: INVALID_BIT_INDEX })
+    }
+}
+
+fn popcount(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok(args[0].count_ones() as NumType)
+}
+
+fn clz(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok((args[0].leading_zeros() - (NumType::BITS - mode.bits())) as NumType)
+}
+
+fn ctz(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok(args[0].trailing_zeros().min(mode.bits()) as NumType)
+}
+
+fn bswap(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok(args[0].swap_bytes() >> (NumType::BITS - mode.bits()))
+}
+
+// The amount is taken modulo the width, so a negative amount rotates the other way
+fn rotl(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok(mode.rotate_left(args[0], args[1]))
+}
+
+fn rotr(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok(mode.rotate_right(args[0], args[1]))
+}
+
+fn bit(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    Ok(1 << bit_index(args, 0, mode)?)
+}
+
+// Bits lo to hi, both included
+fn mask(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    let low = bit_index(args, 0, mode)?;
+    let high = bit_index(args, 1, mode)?;
+    if low > high {
+        return Err(FunctionError::InvalidArgument { index: 0, reason: "The low bit index must not be above the high one" });
+    }
+
+    Ok(NumType::MAX >> (NumType::BITS - 1 - (high - low)) << low)
+}
+
+fn min(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    Ok(args.iter().copied().min_by(|left, right| mode.compare(*left, *right)).unwrap())
+}
+
+fn max(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    Ok(args.iter().copied().max_by(|left, right| mode.compare(*left, *right)).unwrap())
+}
+
+fn abs(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    if mode.compare(args[0], 0).is_ge() || !mode.is_signed() {
+        return Ok(args[0]);
+    }
+
+    mode.sub(0, args[0]).map_err(FunctionError::Overflow)
+}
+
+fn pow(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    match mode.format() {
+        NumFormat::Integer if mode.is_signed() && mode.sign_extend(args[1]) < 0 => {
+            return Err(FunctionError::InvalidArgument { index: 1, reason: NEGATIVE_EXPONENT });
+        },
+        NumFormat::Float if mode.to_real(args[0]) < 0.0 && mode.to_real(args[1]).fract() != 0.0 => {
+            return Err(FunctionError::InvalidArgument { index: 1, reason: FRACTIONAL_EXPONENT });
+        },
+        NumFormat::Fixed { .. } => return Err(FunctionError::Unsupported),
+        _ => {}
+    }
+
+    mode.pow(args[0], args[1]).map_err(FunctionError::Overflow)
+}
+
+// sext(value, bits), also named sign_extend: sign-extends the low `bits` bits of the value to
+// the active width
+fn sext(args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+    require_integer(mode)?;
+    if args[1] == 0 || args[1] > mode.bits() as NumType {
+        return Err(FunctionError::InvalidArgument { index: 1, reason: "Bit count must be between 1 and the width" });
+    }
+
+    let unused_bits = NumType::BITS - args[1] as u32;
+    Ok(mode.truncate((((args[0] << unused_bits) as i128) >> unused_bits) as NumType))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::num_type::Width;
+
+    fn call(name: &str, args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+        Builtin::find(name).unwrap().apply(args, mode)
+    }
+
+    #[test]
+    fn test_counting_functions_honor_width() {
+        let byte = NumMode::new(Width::W8, false);
+        let word = NumMode::new(Width::W32, false);
+
+        assert_eq!(call("popcount", &[0xf0], byte), Ok(4));
+        assert_eq!(call("clz", &[0x10], byte), Ok(3));
+        assert_eq!(call("clz", &[0x10], word), Ok(27));
+        assert_eq!(call("clz", &[0], byte), Ok(8));
+        assert_eq!(call("ctz", &[0x10], word), Ok(4));
+        assert_eq!(call("ctz", &[0], word), Ok(32));
+    }
+
+    #[test]
+    fn test_bswap() {
+        assert_eq!(call("bswap", &[0x12], NumMode::new(Width::W8, false)), Ok(0x12));
+        assert_eq!(call("bswap", &[0x1234], NumMode::new(Width::W16, false)), Ok(0x3412));
+        assert_eq!(call("bswap", &[0x1234_5678], NumMode::new(Width::W32, false)), Ok(0x7856_3412));
+    }
+
+    #[test]
+    fn test_rotations() {
+        let byte = NumMode::new(Width::W8, false);
+
+        assert_eq!(call("rotl", &[0x81, 1], byte), Ok(0x03));
+        assert_eq!(call("rotr", &[0x81, 1], byte), Ok(0xc0));
+        assert_eq!(call("rotl", &[0x81, 8], byte), Ok(0x81));
+        assert_eq!(call("rotr", &[0x12, 0], byte), Ok(0x12));
+        // -1 in a signed byte rotates right by one
+        assert_eq!(call("rotl", &[0x81, 0xff], NumMode::new(Width::W8, true)), Ok(0xc0));
+    }
+
+    #[test]
+    fn test_bit_and_mask() {
+        let byte = NumMode::new(Width::W8, false);
+
+        assert_eq!(call("bit", &[7], byte), Ok(0x80));
+        assert_eq!(call("bit", &[8], byte), Err(FunctionError::InvalidArgument { index: 0, reason: INVALID_BIT_INDEX }));
+        assert_eq!(call("mask", &[4, 7], byte), Ok(0xf0));
+        assert_eq!(call("mask", &[0, 127], NumMode::new(Width::W128, false)), Ok(NumType::MAX));
+        assert!(matches!(call("mask", &[5, 4], byte), Err(FunctionError::InvalidArgument { index: 0, .. })));
+    }
+
+    #[test]
+    fn test_signed_functions() {
+        let byte = NumMode::new(Width::W8, true);
+
+        assert_eq!(call("min", &[0xff, 1, 0x80], byte), Ok(0x80));
+        assert_eq!(call("max", &[0xff, 1, 0x80], byte), Ok(1));
+        assert_eq!(call("max", &[0xff, 1], NumMode::new(Width::W8, false)), Ok(0xff));
+        assert_eq!(call("abs", &[0xfe], byte), Ok(2));
+        assert_eq!(call("abs", &[0x80], byte), Err(FunctionError::Overflow(Overflow { wrapped: 0x80, positive: true })));
+        assert_eq!(call("sext", &[0x0f, 4], byte), Ok(0xff));
+        assert_eq!(call("sext", &[0x07, 4], byte), Ok(0x07));
+        assert_eq!(call("sign_extend", &[0x0f, 4], byte), Ok(0xff));
+        assert!(matches!(call("sext", &[0x07, 9], byte), Err(FunctionError::InvalidArgument { index: 1, .. })));
+        assert_eq!(call("pow", &[0xfe, 3], byte), Ok(0xf8));
+        assert!(matches!(call("pow", &[2, 0xff], byte), Err(FunctionError::InvalidArgument { index: 1, .. })));
+    }
+
+    #[test]
+    fn test_arity() {
+        assert!(Arity::Exactly(2).accepts(2));
+        assert!(!Arity::AtLeast(2).accepts(1));
+        assert_eq!(Arity::AtLeast(2).to_string(), "at least 2 arguments");
+        assert!(Builtin::ALL.iter().all(|builtin| match builtin.arity {
+            Arity::Exactly(count) => count <= Builtin::MAX_FIXED_ARGS,
+            Arity::AtLeast(_) => true
+        }));
+    }
+}
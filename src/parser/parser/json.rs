@@ -0,0 +1,95 @@
+use std::fmt::Write;
+
+use super::error::ParseError;
+use super::format::{format_base, Base};
+use super::num_type::Value;
+use super::numeric::Numeric;
+use super::structs::span::Span;
+
+// JSON for the tools reading results. Values are strings in every base, as the numbers of the
+// reader may not hold them, and spans are byte offsets into the input.
+
+impl<N: Numeric> Value<N> {
+    // {"bases":{"dec":"-1","sdec":"-1","udec":"255",...},"width":8,"signed":true,"format":"integer","overflowed":false}
+    pub fn to_json(&self) -> String {
+        let bases: Vec<String> = [Base::Decimal].iter().chain(Base::ALL.iter())
+            .map(|base| format!("{}:{}", json_string(base.label()), json_string(&format_base(self, *base))))
+            .collect();
+        let (_, width) = self.raw().bit_pattern(self.mode());
+
+        format!(
+            r#"{{"bases":{{{}}},"width":{},"signed":{},"format":{},"overflowed":{}}}"#,
+            bases.join(","),
+            width,
+            N::is_signed(self.mode()),
+            json_string(N::format_name(self.mode())),
+            self.overflowed()
+        )
+    }
+}
+
+impl ParseError {
+    // {"kind":"division_by_zero","message":"Division by zero","span":{"start":2,"end":3}}
+    pub fn to_json(&self) -> String {
+        error_json(self.kind(), &self.to_string(), self.span())
+    }
+}
+
+// An error object as ParseError::to_json gives, for the errors found outside the parser
+pub fn error_json(kind: &str, message: &str, span: Span) -> String {
+    format!(
+        r#"{{"kind":{},"message":{},"span":{{"start":{},"end":{}}}}}"#,
+        json_string(kind),
+        json_string(message),
+        span.start,
+        span.end
+    )
+}
+
+// The text as a JSON string, quoted and escaped
+pub fn json_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c)
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::num_type::{NumMode, Width};
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("1 + x"), r#""1 + x""#);
+        assert_eq!(json_string("say \"hi\"\\\n\t\u{1}é"), r#""say \"hi\"\\\n\t\u0001é""#);
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        let value = Value::new(0xff, NumMode::new(Width::W8, true), true);
+        assert_eq!(
+            value.to_json(),
+            r#"{"bases":{"dec":"-1","sdec":"-1","udec":"255","hex":"0xff","oct":"0o377","bin":"1111 1111"},"width":8,"signed":true,"format":"integer","overflowed":true}"#
+        );
+    }
+
+    #[test]
+    fn test_error_to_json() {
+        let error = ParseError::UndefinedVariable { name: "x".to_string(), span: Span::new(4, 5) };
+        assert_eq!(error.to_json(), r#"{"kind":"undefined_variable","message":"Undefined variable \"x\"","span":{"start":4,"end":5}}"#);
+        assert_eq!(error_json("command", "Unknown \"x\"", Span::new(0, 2)), r#"{"kind":"command","message":"Unknown \"x\"","span":{"start":0,"end":2}}"#);
+    }
+}
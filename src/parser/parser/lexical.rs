@@ -1,136 +1,562 @@
+use super::enums::syntax_profile::SyntaxProfile;
 use super::enums::token::Token;
+use super::error::ParseError;
+use super::limits::EvalLimits;
+use super::structs::span::Span;
 
+// Maximal munch scanner: every token is the longest one starting where the previous one ended,
+// and every character of the input ends up in a token, is whitespace, or is reported.
 pub struct LexicalAnalyzer<'a> {
-    operation_string: &'a String
+    operation_string: &'a str,
+    profile: SyntaxProfile,
+    symbols: Vec<&'static str>,
+    limits: EvalLimits
 }
 
-impl LexicalAnalyzer<'_> {
-    pub fn new(operation_string: &String) -> LexicalAnalyzer {
+impl<'a> LexicalAnalyzer<'a> {
+    pub fn new(operation_string: &'a str, profile: SyntaxProfile) -> LexicalAnalyzer<'a> {
         LexicalAnalyzer {
-            operation_string
+            operation_string,
+            profile,
+            symbols: profile.symbols(),
+            limits: EvalLimits::default()
         }
     }
 
-    pub fn tokenize(&self) -> Result<Vec<Token>, String> {
-        let mut tokens = Vec::new();
+    pub fn with_limits(self, limits: EvalLimits) -> LexicalAnalyzer<'a> {
+        LexicalAnalyzer { limits, ..self }
+    }
 
-        let radix = 10; //TODO: Chose the radix dynamically
+    // Stops at the first token past the limit, so that nothing of an oversized input is scanned
+    pub fn tokenize(&self) -> Result<Vec<(Token<'a>, Span)>, ParseError> {
+        let length = self.operation_string.len();
+        let max_input_length = self.limits.max_input_length();
+        if length > max_input_length {
+            let mut start = max_input_length;
+            while !self.operation_string.is_char_boundary(start) {
+                start -= 1;
+            }
+            return Err(ParseError::InputTooLong { limit: max_input_length, span: Span::new(start, length) });
+        }
 
-        let bytes = self.operation_string.as_bytes();
-        let mut current_index = 0;
-        while current_index < bytes.len() {
-            let current_char = bytes[current_index] as char;
+        let mut tokens = Vec::new();
 
+        let mut current_index = 0;
+        while let Some(current_char) = self.operation_string[current_index..].chars().next() {
             if current_char.is_whitespace() {
-                current_index += 1;
+                current_index += current_char.len_utf8();
                 continue;
             }
 
-            if current_char == 'l' {
-                // Temporal
-                if current_index + 3 >= bytes.len() {
-                    current_index += 1;
-                    continue;
-                }
-
-                let op = &self.operation_string[current_index..current_index + 3];
-                if op == "lsl" || op == "lsr" {
-                    tokens.push(Token::Operator(op));
-                    current_index += 3;
-                    continue;
-                }
+            let (token, next_index) = self.scan_token(current_index)?;
+            if tokens.len() == self.limits.max_tokens() {
+                return Err(ParseError::TooManyTokens { limit: self.limits.max_tokens(), span: Span::new(current_index, next_index) });
             }
-            else if current_char == 'a' {
-                // Temporal
-                if current_index + 3 >= bytes.len() {
-                    current_index += 1;
-                    continue;
-                }
+            tokens.push((token, Span::new(current_index, next_index)));
+            current_index = next_index;
+        }
 
-                let op = &self.operation_string[current_index..current_index + 3];
-                if op == "asl" || op == "asr" || op == "and" {
-                    tokens.push(Token::Operator(op));
-                    current_index += 3;
-                    continue;
-                }
+        Ok(tokens)
+    }
+
+    // Returns the token starting at the index and the index right after it
+    fn scan_token(&self, begin_index: usize) -> Result<(Token<'a>, usize), ParseError> {
+        let rest = &self.operation_string[begin_index..];
+        let first_byte = rest.as_bytes()[0];
+
+        if is_word_byte(first_byte) {
+            return self.scan_word(begin_index);
+        }
+
+        if let Some(symbol) = self.symbols.iter().find(|symbol| rest.starts_with(*symbol)) {
+            return Ok((Token::Operator(&rest[..symbol.len()]), begin_index + symbol.len()));
+        }
+
+        let token = match first_byte {
+            b'(' => Token::ParenthesisOpen,
+            b')' => Token::ParenthesisClose,
+            b'?' => Token::Question,
+            b':' => Token::Colon,
+            b',' => Token::Comma,
+            b'=' => Token::Assign,
+            _ => {
+                let found = rest.chars().next().unwrap();
+
+                return Err(ParseError::UnexpectedChar {
+                    found,
+                    span: Span::new(begin_index, begin_index + found.len_utf8())
+                });
             }
-            else if current_char == 'o' {
-                // Temporal
-                if current_index + 2 >= bytes.len() {
-                    current_index += 1;
-                    continue;
-                }
+        };
 
-                let op = &self.operation_string[current_index..current_index + 2];
-                if op == "or" {
-                    tokens.push(Token::Operator(op));
-                    current_index += 2;
-                    continue;
-                }
+        Ok((token, begin_index + 1))
+    }
+
+    // A word is a run of letters, digits and underscores, read whole: a number when it starts
+    // with a digit, otherwise a keyword or an identifier. So `or_x`, `and3` and `ffh` are
+    // identifiers, and `5and3` is an invalid number.
+    fn scan_word(&self, begin_index: usize) -> Result<(Token<'a>, usize), ParseError> {
+        let bytes = self.operation_string.as_bytes();
+
+        if let Some(length) = real_length(&bytes[begin_index..]) {
+            return Ok((Token::Number(&self.operation_string[begin_index..begin_index + length], 10), begin_index + length));
+        }
+
+        let mut word_end = begin_index;
+        while word_end < bytes.len() && is_word_byte(bytes[word_end]) {
+            word_end += 1;
+        }
+
+        let word = &self.operation_string[begin_index..word_end];
+        if let Some(token) = number(word) {
+            return Ok((token, word_end));
+        }
+
+        if word.starts_with(|c: char| c.is_ascii_digit()) {
+            return Err(ParseError::InvalidNumber { span: Span::new(begin_index, word_end) });
+        }
+
+        if self.profile.keywords().contains(&word) {
+            return Ok((Token::Operator(word), word_end));
+        }
+
+        Ok((Token::Identifier(word), word_end))
+    }
+}
+
+fn is_word_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_'
+}
+
+// A decimal number with a fraction, an exponent or both, as in 1.5, 2e-3 or 1_000.25e3. The
+// word must end there, so 1e5h is still a hex number. None for any other word.
+fn real_length(bytes: &[u8]) -> Option<usize> {
+    let digits_end = |begin: usize| {
+        begin + bytes[begin..].iter().take_while(|byte| byte.is_ascii_digit() || **byte == b'_').count()
+    };
+    let is_digit_at = |index: usize| bytes.get(index).is_some_and(u8::is_ascii_digit);
+
+    if !is_digit_at(0) {
+        return None;
+    }
+
+    let integer_end = digits_end(0);
+    let mut end = integer_end;
+    if bytes.get(end) == Some(&b'.') && is_digit_at(end + 1) {
+        end = digits_end(end + 1);
+    }
+
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign_length = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        if is_digit_at(end + 1 + sign_length) {
+            end = digits_end(end + 1 + sign_length);
+        }
+    }
+
+    let word_continues = bytes.get(end).is_some_and(|byte| is_word_byte(*byte));
+    (end > integer_end && !word_continues).then_some(end)
+}
+
+// Numbers can be written as 0x1f, 0b101, 0o17 or with a suffix (1fh, 101b, 17o, 17q),
+// and use underscores to group digits. Suffixed numbers start with a decimal digit, as in
+// 0ffh, so that words such as `each` stay identifiers.
+fn number(word: &str) -> Option<Token<'_>> {
+    let digits_of = |digits: &str, radix: u32| {
+        digits.starts_with(|c: char| c.is_ascii_digit()) && digits.chars().all(|c| c.is_digit(radix) || c == '_')
+    };
+
+    for (prefix, radix) in [("0x", 16), ("0b", 2), ("0o", 8)] {
+        if let Some(digits) = word.strip_prefix(prefix) {
+            // A prefix alone is not the digit 0 with a suffix
+            if digits.is_empty() {
+                return None;
             }
-            else if current_char == 'x' {
-                // Temporal
-                if current_index + 3 >= bytes.len() {
-                    current_index += 1;
-                    continue;
-                }
+            if digits.chars().any(|c| c.is_digit(radix)) && digits.chars().all(|c| c.is_digit(radix) || c == '_') {
+                return Some(Token::Number(digits, radix));
+            }
+        }
+    }
 
-                let op = &self.operation_string[current_index..current_index + 3];
-                if op == "xor" {
-                    tokens.push(Token::Operator(op));
-                    current_index += 3;
-                    continue;
-                }
+    if digits_of(word, 10) {
+        return Some(Token::Number(word, 10));
+    }
+
+    let (digits, suffix) = word.split_at(word.len() - 1);
+    let radix = match suffix {
+        "h" => 16,
+        "o" | "q" => 8,
+        "b" => 2,
+        _ => return None
+    };
+
+    digits_of(digits, radix).then_some(Token::Number(digits, radix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parser::enums::operator::Operator;
+
+    fn assert_tokens(input: &str, expected: Vec<Token>) {
+        let input = input.to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+
+        let tokens: Vec<Token> = lexical_analyzer.tokenize().unwrap().into_iter().map(|(token, _)| token).collect();
+        assert_eq!(tokens, expected);
+    }
+
+    #[test]
+    fn test_tokenize_prefixed_numbers() {
+        assert_tokens("0xff and 0b1010", vec![
+            Token::Number("ff", 16),
+            Token::Operator("and"),
+            Token::Number("1010", 2)
+        ]);
+        assert_tokens("0o755", vec![Token::Number("755", 8)]);
+    }
+
+    #[test]
+    fn test_tokenize_suffixed_numbers() {
+        assert_tokens("0ffh + 1010b", vec![
+            Token::Number("0ff", 16),
+            Token::Operator("+"),
+            Token::Number("1010", 2)
+        ]);
+        assert_tokens("755q - 17o", vec![
+            Token::Number("755", 8),
+            Token::Operator("-"),
+            Token::Number("17", 8)
+        ]);
+
+        // Words that read as hex digits with a suffix are identifiers
+        assert_tokens("ah + each * fad - cafeh", vec![
+            Token::Identifier("ah"),
+            Token::Operator("+"),
+            Token::Identifier("each"),
+            Token::Operator("*"),
+            Token::Identifier("fad"),
+            Token::Operator("-"),
+            Token::Identifier("cafeh")
+        ]);
+
+        for input in ["0b", "0o", "0x", "0b_"] {
+            let input = input.to_string();
+            let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+            assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::InvalidNumber { span: Span::new(0, input.len()) }), "{}", input);
+        }
+        assert_tokens("0bh", vec![Token::Number("0b", 16)]);
+    }
+
+    #[test]
+    fn test_tokenize_digit_separators() {
+        assert_tokens("0xffff_0000", vec![Token::Number("ffff_0000", 16)]);
+        assert_tokens("1_000_000", vec![Token::Number("1_000_000", 10)]);
+    }
+
+    #[test]
+    fn test_tokenize_words_are_read_whole() {
+        assert_tokens("or_x and3 nota", vec![
+            Token::Identifier("or_x"),
+            Token::Identifier("and3"),
+            Token::Identifier("nota")
+        ]);
+
+        let input = "1 + 5and3".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+        assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::InvalidNumber { span: Span::new(4, 9) }));
+
+        let input = "0b102".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+        assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::InvalidNumber { span: Span::new(0, 5) }));
+    }
+
+    #[test]
+    fn test_tokenize_real_numbers() {
+        assert_tokens("1.5 + 2e-3 * 1_000.25e+3", vec![
+            Token::Number("1.5", 10),
+            Token::Operator("+"),
+            Token::Number("2e-3", 10),
+            Token::Operator("*"),
+            Token::Number("1_000.25e+3", 10)
+        ]);
+
+        // Hex numbers may contain an e, and a trailing sign is an operator
+        assert_tokens("1e5h - 0x1e-3", vec![
+            Token::Number("1e5", 16),
+            Token::Operator("-"),
+            Token::Number("1e", 16),
+            Token::Operator("-"),
+            Token::Number("3", 10)
+        ]);
+
+        let input = "1.x".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+        assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::UnexpectedChar { found: '.', span: Span::new(1, 2) }));
+    }
+
+    #[test]
+    fn test_tokenize_spans() {
+        let input = "0x1f + (0fh)".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+
+        let spans: Vec<Span> = lexical_analyzer.tokenize().unwrap().into_iter().map(|(_, span)| span).collect();
+        assert_eq!(spans, vec![Span::new(0, 4), Span::new(5, 6), Span::new(7, 8), Span::new(8, 11), Span::new(11, 12)]);
+    }
+
+    #[test]
+    fn test_tokenize_unexpected_char() {
+        let input = "1 + $".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+
+        assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::UnexpectedChar { found: '$', span: Span::new(4, 5) }));
+    }
+
+    #[test]
+    fn test_tokenize_identifiers() {
+        assert_tokens("mask = 0xff00", vec![
+            Token::Identifier("mask"),
+            Token::Assign,
+            Token::Number("ff00", 16)
+        ]);
+        assert_tokens("reg1 and _ or android", vec![
+            Token::Identifier("reg1"),
+            Token::Operator("and"),
+            Token::Identifier("_"),
+            Token::Operator("or"),
+            Token::Identifier("android")
+        ]);
+        assert_tokens("cafe + a", vec![
+            Token::Identifier("cafe"),
+            Token::Operator("+"),
+            Token::Identifier("a")
+        ]);
+    }
+
+    #[test]
+    fn test_tokenize_function_call() {
+        assert_tokens("rotl(x, 4)", vec![
+            Token::Identifier("rotl"),
+            Token::ParenthesisOpen,
+            Token::Identifier("x"),
+            Token::Comma,
+            Token::Number("4", 10),
+            Token::ParenthesisClose
+        ]);
+    }
+
+    #[test]
+    fn test_tokenize_keyword_at_end() {
+        assert_tokens("not", vec![Token::Operator("not")]);
+        assert_tokens("5 or", vec![Token::Number("5", 10), Token::Operator("or")]);
+    }
+
+    #[test]
+    fn test_tokenize_unexpected_multibyte_char() {
+        let input = "1 + é".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::default());
+
+        assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::UnexpectedChar { found: 'é', span: Span::new(4, 6) }));
+    }
+
+    #[test]
+    fn test_tokenize_c_symbols() {
+        assert_tokens("~a << 2 >>> 1 >> 3 & b | c ^ d", vec![
+            Token::Operator("~"),
+            Token::Identifier("a"),
+            Token::Operator("<<"),
+            Token::Number("2", 10),
+            Token::Operator(">>>"),
+            Token::Number("1", 10),
+            Token::Operator(">>"),
+            Token::Number("3", 10),
+            Token::Operator("&"),
+            Token::Identifier("b"),
+            Token::Operator("|"),
+            Token::Identifier("c"),
+            Token::Operator("^"),
+            Token::Identifier("d")
+        ]);
+    }
+
+    #[test]
+    fn test_tokenize_syntax_profiles() {
+        let input = "a and b".to_string();
+        let tokens = LexicalAnalyzer::new(&input, SyntaxProfile::CLike).tokenize().unwrap();
+        assert_eq!(tokens[1], (Token::Identifier("and"), Span::new(2, 5)));
+
+        let input = "a & b".to_string();
+        let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::Keywords);
+        assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::UnexpectedChar { found: '&', span: Span::new(2, 3) }));
+    }
+
+    #[test]
+    fn test_tokenize_comparisons() {
+        assert_tokens("!a != b && c <= 1 || d >= 2 == (e < f > g) ? h : i", vec![
+            Token::Operator("!"),
+            Token::Identifier("a"),
+            Token::Operator("!="),
+            Token::Identifier("b"),
+            Token::Operator("&&"),
+            Token::Identifier("c"),
+            Token::Operator("<="),
+            Token::Number("1", 10),
+            Token::Operator("||"),
+            Token::Identifier("d"),
+            Token::Operator(">="),
+            Token::Number("2", 10),
+            Token::Operator("=="),
+            Token::ParenthesisOpen,
+            Token::Identifier("e"),
+            Token::Operator("<"),
+            Token::Identifier("f"),
+            Token::Operator(">"),
+            Token::Identifier("g"),
+            Token::ParenthesisClose,
+            Token::Question,
+            Token::Identifier("h"),
+            Token::Colon,
+            Token::Identifier("i")
+        ]);
+        assert_tokens("a&&b&c", vec![
+            Token::Identifier("a"),
+            Token::Operator("&&"),
+            Token::Identifier("b"),
+            Token::Operator("&"),
+            Token::Identifier("c")
+        ]);
+    }
+
+    // Tokenizes and checks that the tokens, put back together, are the input without whitespace
+    fn tokenize_whole(input: &str, profile: SyntaxProfile) -> Vec<Token<'_>> {
+        let tokens = LexicalAnalyzer::new(input, profile).tokenize().unwrap_or_else(|err| panic!("{:?}: {}", input, err));
+
+        let covered: String = tokens.iter().map(|(_, span)| &input[span.start..span.end]).collect();
+        let expected: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+        assert_eq!(covered, expected, "characters dropped from {:?}", input);
+
+        tokens.into_iter().map(|(token, _)| token).collect()
+    }
+
+    // Every operator alone, first, in the middle and last, spaced or packed against parentheses
+    fn operator_positions(operator: &str) -> Vec<(String, Vec<Token<'_>>)> {
+        let op = || Token::Operator(operator);
+        let one = || Token::Number("1", 10);
+        let two = || Token::Number("2", 10);
+
+        vec![
+            (operator.to_string(), vec![op()]),
+            (format!("{} 1", operator), vec![op(), one()]),
+            (format!("{}(1)", operator), vec![op(), Token::ParenthesisOpen, one(), Token::ParenthesisClose]),
+            (format!("1 {} 2", operator), vec![one(), op(), two()]),
+            (format!("\t1\t{}\t2\t", operator), vec![one(), op(), two()]),
+            (format!("(1){}(2)", operator), vec![Token::ParenthesisOpen, one(), Token::ParenthesisClose, op(), Token::ParenthesisOpen, two(), Token::ParenthesisClose]),
+            (format!("1 {}", operator), vec![one(), op()]),
+            (format!("(1){}", operator), vec![Token::ParenthesisOpen, one(), Token::ParenthesisClose, op()]),
+            (format!("x {} y {} z", operator, operator), vec![Token::Identifier("x"), op(), Token::Identifier("y"), op(), Token::Identifier("z")])
+        ]
+    }
+
+    #[test]
+    fn test_tokenize_every_keyword_at_every_position() {
+        for keyword in Operator::KEYWORDS {
+            for (input, expected) in operator_positions(keyword) {
+                assert_eq!(tokenize_whole(&input, SyntaxProfile::Keywords), expected, "{:?}", input);
+                assert_eq!(tokenize_whole(&input, SyntaxProfile::Both), expected, "{:?}", input);
+
+                let as_identifier: Vec<Token> = expected.into_iter()
+                    .map(|token| if token == Token::Operator(keyword) { Token::Identifier(keyword) } else { token })
+                    .collect();
+                assert_eq!(tokenize_whole(&input, SyntaxProfile::CLike), as_identifier, "{:?}", input);
             }
-            else if current_char == 'n' {
-                // Temporal
-                if current_index + 3 >= bytes.len() {
-                    current_index += 1;
-                    continue;
-                }
 
-                let op = &self.operation_string[current_index..current_index + 3];
-                if op == "not" {
-                    tokens.push(Token::Operator(op));
-                    current_index += 3;
+            // Glued to other word characters, a keyword is part of a longer identifier
+            for word in [format!("{}x", keyword), format!("x{}", keyword), format!("_{}", keyword), format!("{}_", keyword), format!("{}1", keyword)] {
+                if Operator::KEYWORDS.contains(&word.as_str()) {
                     continue;
                 }
+                assert_eq!(tokenize_whole(&format!("1 + {}", word), SyntaxProfile::Both)[2], Token::Identifier(&word));
             }
 
-            if current_char.is_digit(radix) {
-                let begin_index = current_index;
+            let input = format!("1{}", keyword);
+            let lexical_analyzer = LexicalAnalyzer::new(&input, SyntaxProfile::Both);
+            assert_eq!(lexical_analyzer.tokenize(), Err(ParseError::InvalidNumber { span: Span::new(0, input.len()) }));
+        }
+    }
 
-                current_index += 1;
-                while current_index < bytes.len() {
-                    let digit_char = bytes[current_index] as char;
-                    if !digit_char.is_digit(radix) {
-                        break;
-                    }
+    #[test]
+    fn test_tokenize_every_symbol_at_every_position() {
+        for symbol in Operator::C_SYMBOLS.iter().chain(Operator::SYMBOLS.iter()) {
+            let mut positions = operator_positions(symbol);
+            positions.push((format!("1{}2", symbol), vec![Token::Number("1", 10), Token::Operator(symbol), Token::Number("2", 10)]));
+            positions.push((format!("x{}y", symbol), vec![Token::Identifier("x"), Token::Operator(symbol), Token::Identifier("y")]));
 
-                    current_index += 1;
-                }
+            for (input, expected) in positions {
+                assert_eq!(tokenize_whole(&input, SyntaxProfile::Both), expected, "{:?}", input);
 
-                tokens.push(Token::Number(&self.operation_string[begin_index..current_index]));
-            }
-            else if current_char == '+' || current_char == '-' || current_char == '*' || current_char == '/' || current_char == '%' {
-                tokens.push(Token::Operator(&self.operation_string[current_index..current_index + 1]));
-                current_index += 1;
-            }
-            else if current_char == '(' {
-                tokens.push(Token::ParenthesisOpen);
-                current_index += 1;
-            }
-            else if current_char == ')' {
-                tokens.push(Token::ParenthesisClose);
-                current_index += 1;
-            }
-            else {
-                return Err(format!(r#"Unexpected character "{}" at position {}"#, current_char, current_index));
+                if Operator::SYMBOLS.contains(symbol) {
+                    assert_eq!(tokenize_whole(&input, SyntaxProfile::Keywords), expected, "{:?}", input);
+                }
+                else {
+                    // Either rejected, or read as shorter symbols such as `>` `>`
+                    let tokens = LexicalAnalyzer::new(&input, SyntaxProfile::Keywords).tokenize()
+                        .map(|tokens| tokens.into_iter().map(|(token, _)| token).collect::<Vec<Token>>());
+                    assert_ne!(tokens, Ok(expected), "{:?}", input);
+                }
             }
         }
+    }
+
+    #[test]
+    fn test_tokenize_longest_symbol_first() {
+        assert_tokens("a>>>b>>c>=d>e", vec![
+            Token::Identifier("a"),
+            Token::Operator(">>>"),
+            Token::Identifier("b"),
+            Token::Operator(">>"),
+            Token::Identifier("c"),
+            Token::Operator(">="),
+            Token::Identifier("d"),
+            Token::Operator(">"),
+            Token::Identifier("e")
+        ]);
+        assert_tokens("2***3", vec![
+            Token::Number("2", 10),
+            Token::Operator("**"),
+            Token::Operator("*"),
+            Token::Number("3", 10)
+        ]);
+        assert_tokens("a==b=c!=!d", vec![
+            Token::Identifier("a"),
+            Token::Operator("=="),
+            Token::Identifier("b"),
+            Token::Assign,
+            Token::Identifier("c"),
+            Token::Operator("!="),
+            Token::Operator("!"),
+            Token::Identifier("d")
+        ]);
+    }
 
-        //println!("{:?}", tokens);
+    #[test]
+    fn test_tokenize_nothing_dropped_at_end() {
+        assert_tokens("1 or", vec![Token::Number("1", 10), Token::Operator("or")]);
+        assert_tokens("5 not", vec![Token::Number("5", 10), Token::Operator("not")]);
+        assert_tokens("1 + ab", vec![Token::Number("1", 10), Token::Operator("+"), Token::Identifier("ab")]);
+        assert_tokens("x", vec![Token::Identifier("x")]);
+        assert_tokens("a ", vec![Token::Identifier("a")]);
+        assert_tokens(" \u{a0}1", vec![Token::Number("1", 10)]);
+    }
+
+    #[test]
+    fn test_tokenize_limits() {
+        let limited = |input: &'static str, limits: EvalLimits| LexicalAnalyzer::new(input, SyntaxProfile::default()).with_limits(limits).tokenize();
 
-        return Ok(tokens);
+        assert_eq!(limited("1 + 2", EvalLimits::default().with_max_input_length(5)).map(|tokens| tokens.len()), Ok(3));
+        assert_eq!(limited("1 + 22", EvalLimits::default().with_max_input_length(5)), Err(ParseError::InputTooLong { limit: 5, span: Span::new(5, 6) }));
+        // The span starts on the character the limit falls into
+        assert_eq!(limited("1 + \u{e9}", EvalLimits::default().with_max_input_length(5)), Err(ParseError::InputTooLong { limit: 5, span: Span::new(4, 6) }));
+
+        assert_eq!(limited("1 + 2", EvalLimits::default().with_max_tokens(3)).map(|tokens| tokens.len()), Ok(3));
+        assert_eq!(limited("1 + 2 +", EvalLimits::default().with_max_tokens(3)), Err(ParseError::TooManyTokens { limit: 3, span: Span::new(6, 7) }));
     }
-}
\ No newline at end of file
+}
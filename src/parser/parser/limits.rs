@@ -0,0 +1,97 @@
+// Bounds on the work a single expression may ask for, to evaluate input that cannot be trusted.
+// The default only bounds the nesting, which every parser needs to stay clear of the stack.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct EvalLimits {
+    max_input_length: usize,
+    max_tokens: usize,
+    max_depth: usize,
+    max_operations: usize,
+    max_big_int_bits: u64,
+    max_call_depth: usize
+}
+
+// Deep enough for any expression written by hand, shallow enough for the 2 MiB stack of a
+// spawned thread even in debug builds
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+impl Default for EvalLimits {
+    fn default() -> EvalLimits {
+        EvalLimits {
+            max_input_length: usize::MAX,
+            max_tokens: usize::MAX,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_operations: usize::MAX,
+            max_big_int_bits: u64::MAX,
+            max_call_depth: usize::MAX
+        }
+    }
+}
+
+impl EvalLimits {
+    // Small enough for a line typed into a chat, large enough for anything a person would type
+    pub fn untrusted() -> EvalLimits {
+        EvalLimits {
+            max_input_length: 4096,
+            max_tokens: 1024,
+            max_depth: 64,
+            max_operations: 10_000,
+            max_big_int_bits: 65_536,
+            max_call_depth: 16
+        }
+    }
+
+    // In bytes
+    pub fn with_max_input_length(self, max_input_length: usize) -> EvalLimits {
+        EvalLimits { max_input_length, ..self }
+    }
+
+    pub fn with_max_tokens(self, max_tokens: usize) -> EvalLimits {
+        EvalLimits { max_tokens, ..self }
+    }
+
+    // How deep groups, calls, conditionals and operands may nest. Every level takes stack space,
+    // very deep limits need a thread with a larger stack.
+    pub fn with_max_depth(self, max_depth: usize) -> EvalLimits {
+        EvalLimits { max_depth, ..self }
+    }
+
+    // Operators and function calls applied in one evaluation
+    pub fn with_max_operations(self, max_operations: usize) -> EvalLimits {
+        EvalLimits { max_operations, ..self }
+    }
+
+    // Bits of the magnitude of any literal, variable or intermediate result, for the backends
+    // whose numbers grow without bound. Results are checked before they are computed.
+    pub fn with_max_big_int_bits(self, max_big_int_bits: u64) -> EvalLimits {
+        EvalLimits { max_big_int_bits, ..self }
+    }
+
+    // How deep calls may nest inside the arguments of other calls
+    pub fn with_max_call_depth(self, max_call_depth: usize) -> EvalLimits {
+        EvalLimits { max_call_depth, ..self }
+    }
+
+    pub fn max_input_length(&self) -> usize {
+        self.max_input_length
+    }
+
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn max_operations(&self) -> usize {
+        self.max_operations
+    }
+
+    pub fn max_big_int_bits(&self) -> u64 {
+        self.max_big_int_bits
+    }
+
+    pub fn max_call_depth(&self) -> usize {
+        self.max_call_depth
+    }
+}
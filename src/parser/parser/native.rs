@@ -0,0 +1,458 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::IntErrorKind;
+
+use super::big_int::BigInt;
+use super::functions::{Arity, Builtin, FunctionError};
+use super::num_type::{format_float, NumMode, NumType, Overflow, OverflowPolicy, Value, Width};
+use super::numeric::{ArithmeticError, LiteralError, Numeric, Outcome};
+
+// Prefixed and suffixed literals are read as bit patterns, so 0xff is -1 in an i8 as it is in the
+// i8 mode, decimal literals as numbers of the type
+fn parse_pattern(digits: &str, radix: u32, max: NumType) -> Result<NumType, LiteralError> {
+    match NumType::from_str_radix(digits, radix) {
+        Ok(num) if num <= max => Ok(num),
+        Ok(_) => Err(LiteralError::Overflow),
+        Err(err) if *err.kind() == IntErrorKind::PosOverflow => Err(LiteralError::Overflow),
+        Err(_) => Err(LiteralError::Invalid)
+    }
+}
+
+// The amount modulo the bits of the type and the carry
+fn carry_rotation_steps(amount: i128, bits: u32) -> u32 {
+    amount.rem_euclid(bits as i128 + 1) as u32
+}
+
+// The primitive integers, each its own width and signedness. The mode is the overflow policy.
+// u128 is NumType, whose mode chooses the width as well.
+macro_rules! native_integer {
+    ($($t:ty => $unsigned:ty, $signed:ty;)*) => {$(
+        impl Numeric for $t {
+            type Mode = OverflowPolicy;
+
+            fn parse(digits: &str, radix: u32, _: OverflowPolicy) -> Result<$t, LiteralError> {
+                match radix {
+                    10 => digits.parse::<$t>().map_err(|err| match err.kind() {
+                        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => LiteralError::Overflow,
+                        _ => LiteralError::Invalid
+                    }),
+                    _ => parse_pattern(digits, radix, <$unsigned>::MAX as NumType).map(|num| num as $unsigned as $t)
+                }
+            }
+
+            fn convert(value: &Value<$t>, _: OverflowPolicy) -> Result<$t, Overflow<$t>> {
+                Ok(*value.raw())
+            }
+
+            fn from_bool(value: bool, _: OverflowPolicy) -> $t {
+                value as $t
+            }
+
+            fn is_zero(&self, _: OverflowPolicy) -> bool {
+                *self == 0
+            }
+
+            fn compare(&self, other: &$t, _: OverflowPolicy) -> Ordering {
+                self.cmp(other)
+            }
+
+            fn neg(&self, _: OverflowPolicy) -> Outcome<$t> {
+                self.checked_neg().ok_or(ArithmeticError::Overflow(Overflow { wrapped: self.wrapping_neg(), positive: *self < (0 as $t) }))
+            }
+
+            fn not(&self, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(!self)
+            }
+
+            fn add(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                self.checked_add(*other).ok_or(ArithmeticError::Overflow(Overflow { wrapped: self.wrapping_add(*other), positive: *other > 0 }))
+            }
+
+            fn sub(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                self.checked_sub(*other).ok_or(ArithmeticError::Overflow(Overflow { wrapped: self.wrapping_sub(*other), positive: *other < (0 as $t) }))
+            }
+
+            fn mul(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                let positive = (*self < (0 as $t)) == (*other < (0 as $t));
+                self.checked_mul(*other).ok_or(ArithmeticError::Overflow(Overflow { wrapped: self.wrapping_mul(*other), positive }))
+            }
+
+            fn div(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                match *other {
+                    0 => Err(ArithmeticError::DivisionByZero),
+                    _ => self.checked_div(*other).ok_or(ArithmeticError::Overflow(Overflow { wrapped: self.wrapping_div(*other), positive: true }))
+                }
+            }
+
+            fn rem(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                match *other {
+                    0 => Err(ArithmeticError::DivisionByZero),
+                    _ => Ok(self.wrapping_rem(*other))
+                }
+            }
+
+            // Square and multiply, so an exponent beyond u32 still terminates quickly
+            fn pow(&self, exponent: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                if *exponent < (0 as $t) {
+                    return Err(ArithmeticError::NegativeExponent);
+                }
+
+                let (mut result, mut base, mut remaining, mut overflowed) = (1 as $t, *self, *exponent as $unsigned, false);
+                while remaining > 0 {
+                    if remaining & 1 == 1 {
+                        let (product, overflow) = result.overflowing_mul(base);
+                        (result, overflowed) = (product, overflowed || overflow);
+                    }
+
+                    remaining >>= 1;
+                    if remaining > 0 {
+                        let (square, overflow) = base.overflowing_mul(base);
+                        (base, overflowed) = (square, overflowed || overflow);
+                    }
+                }
+
+                match overflowed {
+                    true => Err(ArithmeticError::Overflow(Overflow { wrapped: result, positive: *self >= (0 as $t) || *exponent % 2 == 0 })),
+                    false => Ok(result)
+                }
+            }
+
+            fn and(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(self & other)
+            }
+
+            fn or(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(self | other)
+            }
+
+            fn xor(&self, other: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(self ^ other)
+            }
+
+            fn lsl(&self, amount: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(self << shift_amount::<$t>(*amount as $unsigned as NumType, <$t>::BITS)?)
+            }
+
+            fn lsr(&self, amount: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(((*self as $unsigned) >> shift_amount::<$t>(*amount as $unsigned as NumType, <$t>::BITS)?) as $t)
+            }
+
+            fn asl(&self, amount: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                let amount = shift_amount::<$t>(*amount as $unsigned as NumType, <$t>::BITS)?;
+                let shifted = self << amount;
+
+                match shifted >> amount == *self {
+                    true => Ok(shifted),
+                    false => Err(ArithmeticError::Overflow(Overflow { wrapped: shifted, positive: *self >= (0 as $t) }))
+                }
+            }
+
+            fn asr(&self, amount: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(((*self as $signed) >> shift_amount::<$t>(*amount as $unsigned as NumType, <$t>::BITS)?) as $t)
+            }
+
+            // The amount is taken modulo the width, so a negative amount rotates the other way
+            fn rol(&self, amount: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(self.rotate_left((*amount as $unsigned % <$t>::BITS as $unsigned) as u32))
+            }
+
+            fn ror(&self, amount: &$t, _: OverflowPolicy) -> Outcome<$t> {
+                Ok(self.rotate_right((*amount as $unsigned % <$t>::BITS as $unsigned) as u32))
+            }
+
+            // One bit at a time over the width and the carry, at most the width of steps. Signed
+            // amounts below zero rotate the other way, as in the integer mode.
+            fn rcl(&self, amount: &$t, carry: bool, _: OverflowPolicy) -> Result<($t, bool), ArithmeticError<$t>> {
+                let steps = carry_rotation_steps(*amount as i128, <$t>::BITS);
+                let (mut value, mut carry) = (*self as $unsigned, carry);
+
+                for _ in 0..steps {
+                    (value, carry) = (value << 1 | carry as $unsigned, value >> (<$t>::BITS - 1) == 1);
+                }
+                Ok((value as $t, carry))
+            }
+
+            fn rcr(&self, amount: &$t, carry: bool, _: OverflowPolicy) -> Result<($t, bool), ArithmeticError<$t>> {
+                let steps = carry_rotation_steps(*amount as i128, <$t>::BITS);
+                let (mut value, mut carry) = (*self as $unsigned, carry);
+
+                for _ in 0..steps {
+                    (value, carry) = (value >> 1 | (carry as $unsigned) << (<$t>::BITS - 1), value & 1 == 1);
+                }
+                Ok((value as $t, carry))
+            }
+
+            // The bit patterns go through the integer mode of the same width and signedness, on
+            // the stack. Functions of any number of arguments only compare them.
+            fn apply(builtin: &Builtin, args: &[$t], policy: OverflowPolicy) -> Result<$t, FunctionError<$t>> {
+                if let Arity::AtLeast(_) = builtin.arity {
+                    return builtin.apply_numeric(args, policy);
+                }
+
+                let mode = NumMode::new(Width::from_bits(<$t>::BITS).unwrap(), <$t>::MIN != 0).with_overflow_policy(policy);
+                let mut patterns = [0 as NumType; Builtin::MAX_FIXED_ARGS];
+                for (pattern, arg) in patterns.iter_mut().zip(args) {
+                    *pattern = *arg as $unsigned as NumType;
+                }
+
+                match builtin.apply(&patterns[..args.len()], mode) {
+                    Ok(result) => Ok(result as $unsigned as $t),
+                    Err(FunctionError::Overflow(Overflow { wrapped, positive })) => Err(FunctionError::Overflow(Overflow { wrapped: wrapped as $unsigned as $t, positive })),
+                    Err(FunctionError::InvalidArgument { index, reason }) => Err(FunctionError::InvalidArgument { index, reason }),
+                    Err(FunctionError::Unsupported) => Err(FunctionError::Unsupported)
+                }
+            }
+
+            fn overflow_policy(policy: OverflowPolicy) -> OverflowPolicy {
+                policy
+            }
+
+            fn saturated(positive: bool, _: OverflowPolicy) -> $t {
+                if positive { <$t>::MAX } else { <$t>::MIN }
+            }
+
+            fn format_name(_: OverflowPolicy) -> &'static str {
+                stringify!($t)
+            }
+
+            fn is_signed(_: OverflowPolicy) -> bool {
+                <$t>::MIN != 0
+            }
+
+            fn fmt_value(&self, _: OverflowPolicy, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self)
+            }
+
+            fn bit_pattern(&self, _: OverflowPolicy) -> (BigInt, u32) {
+                (BigInt::from(*self as $unsigned as NumType), <$t>::BITS)
+            }
+        }
+    )*};
+}
+
+native_integer! {
+    i8 => u8, i8;
+    i16 => u16, i16;
+    i32 => u32, i32;
+    i64 => u64, i64;
+    i128 => u128, i128;
+    u8 => u8, i8;
+    u16 => u16, i16;
+    u32 => u32, i32;
+    u64 => u64, i64;
+}
+
+fn shift_amount<N>(amount: NumType, bits: u32) -> Result<u32, ArithmeticError<N>> {
+    match amount < bits as NumType {
+        true => Ok(amount as u32),
+        false => Err(ArithmeticError::InvalidShift)
+    }
+}
+
+fn check_float(exact: f64) -> Outcome<f64> {
+    match exact.is_finite() {
+        true => Ok(exact),
+        false => Err(ArithmeticError::Overflow(Overflow { wrapped: exact, positive: exact > 0.0 }))
+    }
+}
+
+// Double precision floats, without the bitwise operators
+impl Numeric for f64 {
+    type Mode = OverflowPolicy;
+
+    fn parse(digits: &str, radix: u32, _: OverflowPolicy) -> Result<f64, LiteralError> {
+        let real = match radix {
+            10 => digits.parse::<f64>().map_err(|_| LiteralError::Invalid)?,
+            _ => parse_pattern(digits, radix, NumType::MAX)? as f64
+        };
+
+        match real.is_finite() {
+            true => Ok(real),
+            false => Err(LiteralError::Overflow)
+        }
+    }
+
+    fn convert(value: &Value<f64>, _: OverflowPolicy) -> Result<f64, Overflow<f64>> {
+        Ok(*value.raw())
+    }
+
+    fn from_bool(value: bool, _: OverflowPolicy) -> f64 {
+        if value { 1.0 } else { 0.0 }
+    }
+
+    fn is_zero(&self, _: OverflowPolicy) -> bool {
+        *self == 0.0
+    }
+
+    fn compare(&self, other: &f64, _: OverflowPolicy) -> Ordering {
+        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+    }
+
+    fn neg(&self, _: OverflowPolicy) -> Outcome<f64> {
+        Ok(-self)
+    }
+
+    fn not(&self, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn add(&self, other: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        check_float(self + other)
+    }
+
+    fn sub(&self, other: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        check_float(self - other)
+    }
+
+    fn mul(&self, other: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        check_float(self * other)
+    }
+
+    fn div(&self, other: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        match *other == 0.0 {
+            true => Err(ArithmeticError::DivisionByZero),
+            false => check_float(self / other)
+        }
+    }
+
+    fn rem(&self, other: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        match *other == 0.0 {
+            true => Err(ArithmeticError::DivisionByZero),
+            false => Ok(self % other)
+        }
+    }
+
+    fn pow(&self, exponent: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        match *self < 0.0 && exponent.fract() != 0.0 {
+            true => Err(ArithmeticError::FractionalExponent),
+            false => check_float(self.powf(*exponent))
+        }
+    }
+
+    fn and(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn or(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn xor(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn lsl(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn lsr(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn asl(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn asr(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn rol(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn ror(&self, _: &f64, _: OverflowPolicy) -> Outcome<f64> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn rcl(&self, _: &f64, _: bool, _: OverflowPolicy) -> Result<(f64, bool), ArithmeticError<f64>> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn rcr(&self, _: &f64, _: bool, _: OverflowPolicy) -> Result<(f64, bool), ArithmeticError<f64>> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn overflow_policy(policy: OverflowPolicy) -> OverflowPolicy {
+        policy
+    }
+
+    fn saturated(positive: bool, _: OverflowPolicy) -> f64 {
+        if positive { f64::MAX } else { f64::MIN }
+    }
+
+    fn format_name(_: OverflowPolicy) -> &'static str {
+        "f64"
+    }
+
+    fn is_signed(_: OverflowPolicy) -> bool {
+        true
+    }
+
+    fn fmt_value(&self, _: OverflowPolicy, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_float(*self))
+    }
+
+    fn bit_pattern(&self, _: OverflowPolicy) -> (BigInt, u32) {
+        (BigInt::from(self.to_bits() as NumType), 64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_native_integer_arithmetic() {
+        let policy = OverflowPolicy::Error;
+
+        assert_eq!(Numeric::add(&100i8, &27, policy), Ok(127));
+        assert_eq!(Numeric::add(&100i8, &28, policy), Err(ArithmeticError::Overflow(Overflow { wrapped: -128, positive: true })));
+        assert_eq!(Numeric::sub(&0u8, &1, policy), Err(ArithmeticError::Overflow(Overflow { wrapped: 255, positive: false })));
+        assert_eq!(Numeric::div(&i16::MIN, &-1, policy), Err(ArithmeticError::Overflow(Overflow { wrapped: i16::MIN, positive: true })));
+        assert_eq!(Numeric::pow(&-2i32, &31, policy), Ok(i32::MIN));
+        assert_eq!(Numeric::pow(&2i32, &31, policy), Err(ArithmeticError::Overflow(Overflow { wrapped: i32::MIN, positive: true })));
+        assert_eq!(Numeric::pow(&1u64, &u64::MAX, policy), Ok(1));
+        assert_eq!(<i8 as Numeric>::parse("ff", 16, policy), Ok(-1));
+        assert_eq!(<u8 as Numeric>::parse("100", 16, policy), Err(LiteralError::Overflow));
+    }
+
+    #[test]
+    fn test_native_integer_decimal_range() {
+        let policy = OverflowPolicy::Error;
+
+        assert_eq!(<i8 as Numeric>::parse("-128", 10, policy), Ok(i8::MIN));
+        assert_eq!(<i8 as Numeric>::parse("127", 10, policy), Ok(i8::MAX));
+        assert_eq!(<i8 as Numeric>::parse("128", 10, policy), Err(LiteralError::Overflow));
+        assert_eq!(<i8 as Numeric>::parse("200", 10, policy), Err(LiteralError::Overflow));
+        assert_eq!(<i16 as Numeric>::parse("-32768", 10, policy), Ok(i16::MIN));
+        assert_eq!(<i16 as Numeric>::parse("32767", 10, policy), Ok(i16::MAX));
+        assert_eq!(<i16 as Numeric>::parse("32768", 10, policy), Err(LiteralError::Overflow));
+        assert_eq!(<i32 as Numeric>::parse("-2147483648", 10, policy), Ok(i32::MIN));
+        assert_eq!(<i32 as Numeric>::parse("2147483647", 10, policy), Ok(i32::MAX));
+        assert_eq!(<i32 as Numeric>::parse("2147483648", 10, policy), Err(LiteralError::Overflow));
+        assert_eq!(<i64 as Numeric>::parse("-9223372036854775808", 10, policy), Ok(i64::MIN));
+        assert_eq!(<i64 as Numeric>::parse("9223372036854775807", 10, policy), Ok(i64::MAX));
+        assert_eq!(<i64 as Numeric>::parse("9223372036854775808", 10, policy), Err(LiteralError::Overflow));
+        assert_eq!(<i128 as Numeric>::parse(&i128::MIN.to_string(), 10, policy), Ok(i128::MIN));
+        assert_eq!(<i128 as Numeric>::parse(&i128::MAX.to_string(), 10, policy), Ok(i128::MAX));
+        assert_eq!(<i128 as Numeric>::parse("170141183460469231731687303715884105728", 10, policy), Err(LiteralError::Overflow));
+        assert_eq!(<u8 as Numeric>::parse("255", 10, policy), Ok(u8::MAX));
+        assert_eq!(<u8 as Numeric>::parse("256", 10, policy), Err(LiteralError::Overflow));
+    }
+
+    #[test]
+    fn test_native_integer_bits() {
+        let policy = OverflowPolicy::Error;
+
+        assert_eq!(Numeric::lsr(&-16i8, &2, policy), Ok(0x3c));
+        assert_eq!(Numeric::asr(&0xf0u8, &2, policy), Ok(0xfc));
+        assert_eq!(Numeric::asl(&0x40i8, &1, policy), Err(ArithmeticError::Overflow(Overflow { wrapped: -128, positive: true })));
+        assert_eq!(Numeric::lsl(&1u32, &32, policy), Err(ArithmeticError::InvalidShift));
+        assert_eq!(Numeric::rol(&-127i8, &-1, policy), Ok(-64));
+        assert_eq!(Numeric::rcl(&0x80u8, &1, false, policy), Ok((0, true)));
+        assert_eq!(Numeric::rcr(&0i128, &1, true, policy), Ok((i128::MIN, false)));
+        assert_eq!(Numeric::rcl(&1i8, &-1, false, policy), Ok((0, true)));
+        assert_eq!(Numeric::rcr(&1i8, &-1, false, policy), Ok((2, false)));
+    }
+}
@@ -1,2 +1,847 @@
-pub type NumType = i32;
-pub type _UnsignedNumType = u32;
\ No newline at end of file
+use std::cmp::Ordering;
+use std::fmt;
+
+use super::numeric::Numeric;
+
+// Every value is stored as its two's complement bit pattern, truncated to the active width,
+// so the same representation serves every width and both signed and unsigned modes.
+pub type NumType = u128;
+pub type SignedNumType = i128;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Width {
+    W8,
+    W16,
+    W32,
+    W64,
+    W128
+}
+
+impl Width {
+    pub fn from_bits(bits: u32) -> Option<Width> {
+        match bits {
+            8 => Some(Width::W8),
+            16 => Some(Width::W16),
+            32 => Some(Width::W32),
+            64 => Some(Width::W64),
+            128 => Some(Width::W128),
+            _ => None
+        }
+    }
+
+    pub fn bits(&self) -> u32 {
+        match self {
+            Width::W8 => 8,
+            Width::W16 => 16,
+            Width::W32 => 32,
+            Width::W64 => 64,
+            Width::W128 => 128
+        }
+    }
+}
+
+// What to do when the result of an operation does not fit in the active width
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum OverflowPolicy {
+    Error,
+    Wrapping,
+    Saturating
+}
+
+impl OverflowPolicy {
+    pub fn from_name(policy: &str) -> Option<OverflowPolicy> {
+        match policy {
+            "error" => Some(OverflowPolicy::Error),
+            "wrap" | "wrapping" => Some(OverflowPolicy::Wrapping),
+            "saturate" | "saturating" => Some(OverflowPolicy::Saturating),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for OverflowPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OverflowPolicy::Error => write!(f, "error"),
+            OverflowPolicy::Wrapping => write!(f, "wrapping"),
+            OverflowPolicy::Saturating => write!(f, "saturating")
+        }
+    }
+}
+
+// An operation whose exact result does not fit in the active width. Keeps the truncated
+// result and the direction of the overflow, needed to wrap or saturate.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Overflow<N = NumType> {
+    pub wrapped: N,
+    pub positive: bool
+}
+
+// How the bit pattern of a value is read: as an integer, as a 64-bit IEEE 754 float, or as a
+// fixed-point number whose low bits hold the fraction
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NumFormat {
+    Integer,
+    Float,
+    Fixed { fraction_bits: u32 }
+}
+
+impl NumFormat {
+    pub fn name(&self) -> &'static str {
+        match self {
+            NumFormat::Integer => "integer",
+            NumFormat::Float => "float",
+            NumFormat::Fixed { .. } => "fixed-point"
+        }
+    }
+}
+
+impl fmt::Display for NumFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NumMode {
+    width: Width,
+    signed: bool,
+    format: NumFormat,
+    overflow_policy: OverflowPolicy
+}
+
+impl Default for NumMode {
+    fn default() -> NumMode {
+        NumMode::new(Width::W32, true)
+    }
+}
+
+impl NumMode {
+    pub fn new(width: Width, signed: bool) -> NumMode {
+        NumMode { width, signed, format: NumFormat::Integer, overflow_policy: OverflowPolicy::Error }
+    }
+
+    // Values are f64, stored as their 64-bit pattern
+    pub fn float() -> NumMode {
+        NumMode { format: NumFormat::Float, ..NumMode::new(Width::W64, true) }
+    }
+
+    // The low `fraction_bits` bits hold the fraction. Products are computed at twice the width,
+    // so fixed-point modes are at most 64 bits wide.
+    pub fn fixed(width: Width, signed: bool, fraction_bits: u32) -> Option<NumMode> {
+        if width.bits() > 64 || fraction_bits > width.bits() - u32::from(signed) {
+            return None;
+        }
+
+        Some(NumMode { format: NumFormat::Fixed { fraction_bits }, ..NumMode::new(width, signed) })
+    }
+
+    // Q notation, the integer bits including the sign bit: q1.15 and its shorthand q15 are
+    // signed 16-bit with 15 fraction bits, uq8.8 is unsigned 16-bit with 8 fraction bits
+    pub fn from_q_notation(name: &str) -> Option<NumMode> {
+        let (signed, format) = match name.strip_prefix("uq") {
+            Some(format) => (false, format),
+            None => (true, name.strip_prefix('q')?)
+        };
+
+        let (integer_bits, fraction_bits) = match format.split_once('.') {
+            Some((integer_bits, fraction_bits)) => (integer_bits.parse::<u32>().ok()?, fraction_bits.parse::<u32>().ok()?),
+            None => {
+                let fraction_bits = format.parse::<u32>().ok()?;
+                (u32::from(signed), fraction_bits)
+            }
+        };
+
+        let width = Width::from_bits(integer_bits.checked_add(fraction_bits)?)?;
+        NumMode::fixed(width, signed, fraction_bits)
+    }
+
+    pub fn format(&self) -> NumFormat {
+        self.format
+    }
+
+    pub fn with_overflow_policy(self, overflow_policy: OverflowPolicy) -> NumMode {
+        NumMode { overflow_policy, ..self }
+    }
+
+    pub fn overflow_policy(&self) -> OverflowPolicy {
+        self.overflow_policy
+    }
+
+    pub fn width(&self) -> Width {
+        self.width
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    pub fn bits(&self) -> u32 {
+        self.width.bits()
+    }
+
+    pub fn mask(&self) -> NumType {
+        NumType::MAX >> (NumType::BITS - self.bits())
+    }
+
+    pub fn truncate(&self, value: NumType) -> NumType {
+        value & self.mask()
+    }
+
+    // Sign-extends the bit pattern from the active width to the full storage width
+    pub fn sign_extend(&self, value: NumType) -> SignedNumType {
+        let unused_bits = NumType::BITS - self.bits();
+        ((value << unused_bits) as SignedNumType) >> unused_bits
+    }
+
+    pub fn fits(&self, value: NumType) -> bool {
+        value <= self.mask()
+    }
+
+    pub fn not(&self, value: NumType) -> NumType {
+        self.truncate(!value)
+    }
+
+    pub fn max(&self) -> NumType {
+        match (self.format, self.signed) {
+            (NumFormat::Float, _) => from_f64(f64::MAX),
+            (_, true) => self.mask() >> 1,
+            (_, false) => self.mask()
+        }
+    }
+
+    pub fn min(&self) -> NumType {
+        match (self.format, self.signed) {
+            (NumFormat::Float, _) => from_f64(f64::MIN),
+            (_, true) => (self.mask() >> 1) + 1,
+            (_, false) => 0
+        }
+    }
+
+    pub fn is_zero(&self, value: NumType) -> bool {
+        match self.format {
+            NumFormat::Float => to_f64(value) == 0.0,
+            _ => value == 0
+        }
+    }
+
+    // True is one, or the largest value when a fixed-point format cannot hold one
+    pub fn from_bool(&self, value: bool) -> NumType {
+        match (self.format, value) {
+            (_, false) => 0,
+            (NumFormat::Integer, true) => 1,
+            (NumFormat::Float, true) => from_f64(1.0),
+            (NumFormat::Fixed { .. }, true) => self.from_real(1.0).unwrap_or(self.max())
+        }
+    }
+
+    // Reads a shift or rotation amount. Fixed-point amounts must be whole numbers, and negative
+    // ones keep their bit pattern, as in the integer mode. Float values are never amounts.
+    pub fn count(&self, value: NumType) -> Option<NumType> {
+        match self.format {
+            NumFormat::Integer => Some(value),
+            NumFormat::Float => None,
+            NumFormat::Fixed { fraction_bits } => {
+                let fraction = value & !(NumType::MAX << fraction_bits);
+                (fraction == 0).then(|| self.truncate((self.sign_extend(value) >> fraction_bits) as NumType))
+            }
+        }
+    }
+
+    // The real number a bit pattern stands for, rounded to the nearest f64
+    pub fn to_real(&self, value: NumType) -> f64 {
+        let integer = if self.signed { self.sign_extend(value) as f64 } else { value as f64 };
+
+        match self.format {
+            NumFormat::Integer => integer,
+            NumFormat::Float => to_f64(value),
+            NumFormat::Fixed { fraction_bits } => integer / 2f64.powi(fraction_bits as i32)
+        }
+    }
+
+    // The bit pattern of a real number, truncated toward zero in the integer mode and rounded
+    // to the nearest in the fixed-point ones
+    pub fn from_real(&self, value: f64) -> Result<NumType, Overflow> {
+        let scaled = match self.format {
+            NumFormat::Integer => value.trunc(),
+            NumFormat::Float => return self.check_float(value),
+            NumFormat::Fixed { fraction_bits } => (value * 2f64.powi(fraction_bits as i32)).round()
+        };
+
+        let wrapped = self.truncate(if scaled < 0.0 { scaled as SignedNumType as NumType } else { scaled as NumType });
+        let fits = match self.signed {
+            true => scaled >= self.sign_extend(self.min()) as f64 && scaled <= self.max() as f64,
+            false => scaled >= 0.0 && scaled <= self.max() as f64
+        };
+
+        match fits && scaled.is_finite() {
+            true => Ok(wrapped),
+            false => Err(Overflow { wrapped, positive: scaled > 0.0 })
+        }
+    }
+
+    // Infinite results overflow. Wrapping keeps the infinity, saturating gives the largest float.
+    fn check_float(&self, exact: f64) -> Result<NumType, Overflow> {
+        match exact.is_finite() {
+            true => Ok(from_f64(exact)),
+            false => Err(Overflow { wrapped: from_f64(exact), positive: exact > 0.0 })
+        }
+    }
+
+    pub fn compare(&self, left: NumType, right: NumType) -> Ordering {
+        if self.format == NumFormat::Float {
+            to_f64(left).partial_cmp(&to_f64(right)).unwrap_or(Ordering::Equal)
+        }
+        else if self.signed {
+            self.sign_extend(left).cmp(&self.sign_extend(right))
+        }
+        else {
+            left.cmp(&right)
+        }
+    }
+
+    pub fn add(&self, left: NumType, right: NumType) -> Result<NumType, Overflow> {
+        if self.format == NumFormat::Float {
+            return self.check_float(to_f64(left) + to_f64(right));
+        }
+
+        let wrapped = self.truncate(left.wrapping_add(right));
+
+        if self.signed {
+            let left = self.sign_extend(left);
+            self.check_signed(left.checked_add(self.sign_extend(right)), wrapped, left >= 0)
+        }
+        else {
+            self.check_unsigned(left.checked_add(right), wrapped, true)
+        }
+    }
+
+    pub fn sub(&self, left: NumType, right: NumType) -> Result<NumType, Overflow> {
+        if self.format == NumFormat::Float {
+            return self.check_float(to_f64(left) - to_f64(right));
+        }
+
+        let wrapped = self.truncate(left.wrapping_sub(right));
+
+        if self.signed {
+            let left = self.sign_extend(left);
+            self.check_signed(left.checked_sub(self.sign_extend(right)), wrapped, left >= 0)
+        }
+        else {
+            self.check_unsigned(left.checked_sub(right), wrapped, false)
+        }
+    }
+
+    pub fn mul(&self, left: NumType, right: NumType) -> Result<NumType, Overflow> {
+        match self.format {
+            NumFormat::Float => return self.check_float(to_f64(left) * to_f64(right)),
+            NumFormat::Fixed { fraction_bits } => return self.fixed_mul(left, right, fraction_bits),
+            NumFormat::Integer => {}
+        }
+
+        let wrapped = self.truncate(left.wrapping_mul(right));
+
+        if self.signed {
+            let (left, right) = (self.sign_extend(left), self.sign_extend(right));
+            self.check_signed(left.checked_mul(right), wrapped, (left < 0) == (right < 0))
+        }
+        else {
+            self.check_unsigned(left.checked_mul(right), wrapped, true)
+        }
+    }
+
+    // The exponent is read as unsigned, a negative exponent must be rejected beforehand. In the
+    // float mode any exponent is allowed, but a negative base needs a whole one.
+    pub fn pow(&self, base: NumType, exponent: NumType) -> Result<NumType, Overflow> {
+        if self.format == NumFormat::Float {
+            return self.check_float(to_f64(base).powf(to_f64(exponent)));
+        }
+
+        let wrapped = self.truncate(wrapping_pow(base, exponent));
+        let small_exponent = u32::try_from(exponent).ok();
+
+        if self.signed {
+            let base = self.sign_extend(base);
+            let exact = match (small_exponent, base) {
+                (Some(exponent), _) => base.checked_pow(exponent),
+                (None, 0 | 1) => Some(base),
+                (None, -1) => Some(if exponent.is_multiple_of(2) { 1 } else { -1 }),
+                (None, _) => None
+            };
+            self.check_signed(exact, wrapped, base >= 0 || exponent.is_multiple_of(2))
+        }
+        else {
+            let exact = match (small_exponent, base) {
+                (Some(exponent), _) => base.checked_pow(exponent),
+                (None, 0 | 1) => Some(base),
+                (None, _) => None
+            };
+            self.check_unsigned(exact, wrapped, true)
+        }
+    }
+
+    // The divisor must not be zero
+    pub fn div(&self, left: NumType, right: NumType) -> Result<NumType, Overflow> {
+        match self.format {
+            NumFormat::Float => return self.check_float(to_f64(left) / to_f64(right)),
+            NumFormat::Fixed { fraction_bits } => return self.fixed_div(left, right, fraction_bits),
+            NumFormat::Integer => {}
+        }
+
+        if self.signed {
+            let (left, right) = (self.sign_extend(left), self.sign_extend(right));
+            let wrapped = self.truncate(left.wrapping_div(right) as NumType);
+            self.check_signed(left.checked_div(right), wrapped, true)
+        }
+        else {
+            Ok(left / right)
+        }
+    }
+
+    // The divisor must not be zero. Fixed-point remainders share the scale of their operands.
+    pub fn rem(&self, left: NumType, right: NumType) -> NumType {
+        if self.format == NumFormat::Float {
+            from_f64(to_f64(left) % to_f64(right))
+        }
+        else if self.signed {
+            self.truncate(self.sign_extend(left).wrapping_rem(self.sign_extend(right)) as NumType)
+        }
+        else {
+            left % right
+        }
+    }
+
+    // Arithmetic shift left is a multiplication by a power of two, so unlike the logical
+    // shift it overflows when significant bits are shifted out
+    pub fn arithmetic_shift_left(&self, value: NumType, amount: NumType) -> Result<NumType, Overflow> {
+        let wrapped = self.shift_left(value, amount);
+        let amount = amount.min(NumType::BITS as NumType - 1) as u32;
+
+        if self.signed {
+            let value = self.sign_extend(value);
+            let shifted = value << amount;
+            self.check_signed(Some(shifted).filter(|shifted| shifted >> amount == value), wrapped, value >= 0)
+        }
+        else {
+            let shifted = value << amount;
+            self.check_unsigned(Some(shifted).filter(|shifted| shifted >> amount == value), wrapped, true)
+        }
+    }
+
+    // Brings a value computed in another mode into this one, overflowing when its numeric
+    // value does not fit, as when a negative value is read in an unsigned mode
+    pub fn convert(&self, value: &Value) -> Result<NumType, Overflow> {
+        if value.mode().format() != self.format {
+            return self.from_real(value.as_real());
+        }
+
+        if value.mode().is_signed() {
+            let exact = value.as_signed();
+            let wrapped = self.truncate(exact as NumType);
+
+            match self.signed {
+                true => self.check_signed(Some(exact), wrapped, exact >= 0),
+                false if exact < 0 => Err(Overflow { wrapped, positive: false }),
+                false => self.check_unsigned(Some(exact as NumType), wrapped, true)
+            }
+        }
+        else {
+            let exact = value.as_unsigned();
+            let wrapped = self.truncate(exact);
+
+            match self.signed {
+                true => self.check_signed(SignedNumType::try_from(exact).ok(), wrapped, true),
+                false => self.check_unsigned(Some(exact), wrapped, true)
+            }
+        }
+    }
+
+    // The product of two fixed-point values has twice the fraction bits, rounded back to nearest.
+    // Both operands are at most 64 bits wide, so the product fits in 128.
+    fn fixed_mul(&self, left: NumType, right: NumType, fraction_bits: u32) -> Result<NumType, Overflow> {
+        let half = (1 << fraction_bits) >> 1;
+
+        if self.signed {
+            let exact = (self.sign_extend(left) * self.sign_extend(right) + half) >> fraction_bits;
+            self.check_signed(Some(exact), self.truncate(exact as NumType), exact >= 0)
+        }
+        else {
+            let exact = (left * right + half as NumType) >> fraction_bits;
+            self.check_unsigned(Some(exact), self.truncate(exact), true)
+        }
+    }
+
+    // The dividend is scaled up first so the quotient keeps its fraction, truncated toward zero
+    fn fixed_div(&self, left: NumType, right: NumType, fraction_bits: u32) -> Result<NumType, Overflow> {
+        if self.signed {
+            let exact = (self.sign_extend(left) << fraction_bits) / self.sign_extend(right);
+            self.check_signed(Some(exact), self.truncate(exact as NumType), exact >= 0)
+        }
+        else {
+            let exact = (left << fraction_bits) / right;
+            self.check_unsigned(Some(exact), self.truncate(exact), true)
+        }
+    }
+
+    fn check_signed(&self, exact: Option<SignedNumType>, wrapped: NumType, positive: bool) -> Result<NumType, Overflow> {
+        match exact {
+            Some(exact) if exact >= self.sign_extend(self.min()) && exact <= self.max() as SignedNumType => Ok(wrapped),
+            Some(exact) => Err(Overflow { wrapped, positive: exact > 0 }),
+            None => Err(Overflow { wrapped, positive })
+        }
+    }
+
+    fn check_unsigned(&self, exact: Option<NumType>, wrapped: NumType, positive: bool) -> Result<NumType, Overflow> {
+        match exact {
+            Some(exact) if exact <= self.max() => Ok(wrapped),
+            _ => Err(Overflow { wrapped, positive })
+        }
+    }
+
+    pub fn shift_left(&self, value: NumType, amount: NumType) -> NumType {
+        if amount >= self.bits() as NumType {
+            return 0;
+        }
+
+        self.truncate(value << amount)
+    }
+
+    pub fn logical_shift_right(&self, value: NumType, amount: NumType) -> NumType {
+        if amount >= self.bits() as NumType {
+            return 0;
+        }
+
+        value >> amount
+    }
+
+    // The amount is taken modulo the width, so a negative amount rotates the other way
+    pub fn rotate_left(&self, value: NumType, amount: NumType) -> NumType {
+        let amount = (amount % self.bits() as NumType) as u32;
+        if amount == 0 {
+            return value;
+        }
+
+        self.truncate(value << amount | value >> (self.bits() - amount))
+    }
+
+    pub fn rotate_right(&self, value: NumType, amount: NumType) -> NumType {
+        let amount = amount % self.bits() as NumType;
+        self.rotate_left(value, self.bits() as NumType - amount)
+    }
+
+    // Rotates the value and the carry as a single number one bit wider than the active width,
+    // the carry being its most significant bit. Returns the value and the new carry. Amounts of
+    // signed modes are read as signed, so a negative amount rotates the other way.
+    pub fn rotate_left_through_carry(&self, value: NumType, amount: NumType, carry: bool) -> (NumType, bool) {
+        self.rotate_through_carry(value, self.carry_rotation_steps(amount), carry)
+    }
+
+    pub fn rotate_right_through_carry(&self, value: NumType, amount: NumType, carry: bool) -> (NumType, bool) {
+        let bits = self.bits() + 1;
+        self.rotate_through_carry(value, (bits - self.carry_rotation_steps(amount)) % bits, carry)
+    }
+
+    // The amount modulo the width and the carry, from 0 to the width
+    fn carry_rotation_steps(&self, amount: NumType) -> u32 {
+        let bits = self.bits() + 1;
+        match self.signed {
+            true => self.sign_extend(amount).rem_euclid(bits as SignedNumType) as u32,
+            false => (amount % bits as NumType) as u32
+        }
+    }
+
+    fn rotate_through_carry(&self, value: NumType, steps: u32, carry: bool) -> (NumType, bool) {
+        let bits = self.bits();
+        if steps == 0 {
+            return (value, carry);
+        }
+
+        let rotated = value.checked_shl(steps).unwrap_or(0)
+            | NumType::from(carry) << (steps - 1)
+            | value.checked_shr(bits + 1 - steps).unwrap_or(0);
+
+        (self.truncate(rotated), value >> (bits - steps) & 1 == 1)
+    }
+
+    // The vacated bits are filled with the most significant bit of the active width
+    pub fn arithmetic_shift_right(&self, value: NumType, amount: NumType) -> NumType {
+        let amount = amount.min(self.bits() as NumType - 1);
+        self.truncate((self.sign_extend(value) >> amount) as NumType)
+    }
+
+    // Prints the value a bit pattern stands for in this mode
+    pub fn fmt_value(&self, value: NumType, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.format {
+            NumFormat::Float => write!(f, "{}", format_float(self.to_real(value))),
+            NumFormat::Fixed { fraction_bits } => match self.signed {
+                true if self.sign_extend(value) < 0 => write!(f, "-{}", format_fixed(self.sign_extend(value).unsigned_abs(), fraction_bits)),
+                _ => write!(f, "{}", format_fixed(value, fraction_bits))
+            },
+            NumFormat::Integer if self.signed => write!(f, "{}", self.sign_extend(value)),
+            NumFormat::Integer => write!(f, "{}", value)
+        }
+    }
+}
+
+fn to_f64(value: NumType) -> f64 {
+    f64::from_bits(value as u64)
+}
+
+fn from_f64(value: f64) -> NumType {
+    value.to_bits() as NumType
+}
+
+// Exponentiation by squaring modulo 2^128, for exponents of any size
+fn wrapping_pow(mut base: NumType, mut exponent: NumType) -> NumType {
+    let mut result: NumType = 1;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result.wrapping_mul(base);
+        }
+
+        base = base.wrapping_mul(base);
+        exponent >>= 1;
+    }
+
+    result
+}
+
+impl fmt::Display for NumMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let sign = if self.signed { "" } else { "u" };
+
+        match self.format {
+            NumFormat::Integer => write!(f, "{}{}", if self.signed { "i" } else { "u" }, self.bits())?,
+            NumFormat::Float => write!(f, "f64")?,
+            NumFormat::Fixed { fraction_bits } => write!(f, "{}q{}.{}", sign, self.bits() - fraction_bits, fraction_bits)?
+        }
+
+        write!(f, " (overflow: {})", self.overflow_policy)
+    }
+}
+
+// A result along with the mode it was computed in, which tells how to read it
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Value<N: Numeric = NumType> {
+    raw: N,
+    mode: N::Mode,
+    overflowed: bool
+}
+
+impl<N: Numeric> Value<N> {
+    // The raw number must be one of the mode, as within its width
+    pub fn from_raw(raw: N, mode: N::Mode, overflowed: bool) -> Value<N> {
+        Value { raw, mode, overflowed }
+    }
+
+    pub fn raw(&self) -> &N {
+        &self.raw
+    }
+
+    pub fn mode(&self) -> N::Mode {
+        self.mode
+    }
+
+    // Set when some operation overflowed and its result was wrapped or saturated
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+}
+
+impl<N: Numeric> fmt::Display for Value<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.raw.fmt_value(self.mode, f)
+    }
+}
+
+impl Value {
+    pub fn new(raw: NumType, mode: NumMode, overflowed: bool) -> Value {
+        Value::from_raw(raw, mode, overflowed)
+    }
+
+    pub fn as_signed(&self) -> SignedNumType {
+        self.mode.sign_extend(self.raw)
+    }
+
+    pub fn as_unsigned(&self) -> NumType {
+        self.raw
+    }
+
+    pub fn as_real(&self) -> f64 {
+        self.mode.to_real(self.raw)
+    }
+}
+
+// Fixed-point values are printed exactly, since their fraction is a sum of powers of two
+fn format_fixed(magnitude: NumType, fraction_bits: u32) -> String {
+    let integer = magnitude.checked_shr(fraction_bits).unwrap_or(0);
+    let mut fraction = magnitude & !NumType::MAX.checked_shl(fraction_bits).unwrap_or(0);
+    let mut digits = integer.to_string();
+
+    if fraction != 0 {
+        digits.push('.');
+    }
+    while fraction != 0 {
+        fraction *= 10;
+        digits.push(char::from_digit((fraction >> fraction_bits) as u32, 10).unwrap());
+        fraction &= !(NumType::MAX << fraction_bits);
+    }
+
+    digits
+}
+
+// Very large and very small floats are printed with an exponent
+pub(super) fn format_float(value: f64) -> String {
+    let magnitude = value.abs();
+
+    if magnitude == 0.0 || (1e-5..1e16).contains(&magnitude) {
+        value.to_string()
+    }
+    else {
+        format!("{:e}", value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_not_respects_width() {
+        assert_eq!(NumMode::new(Width::W8, false).not(0), 255);
+        assert_eq!(NumMode::new(Width::W16, false).not(0), 0xffff);
+        assert_eq!(Value::new(NumMode::new(Width::W8, true).not(0), NumMode::new(Width::W8, true), false).as_signed(), -1);
+    }
+
+    #[test]
+    fn test_shifts_respect_width() {
+        let byte = NumMode::new(Width::W8, false);
+        assert_eq!(byte.logical_shift_right(0x80, 1), 0x40);
+        assert_eq!(byte.arithmetic_shift_right(0x80, 1), 0xc0);
+        assert_eq!(byte.shift_left(0x81, 1), 0x02);
+
+        let word = NumMode::new(Width::W32, false);
+        assert_eq!(word.logical_shift_right(0x80, 1), 0x40);
+        assert_eq!(word.arithmetic_shift_right(0x80, 1), 0x40);
+        assert_eq!(word.arithmetic_shift_right(0x8000_0000, 4), 0xf800_0000);
+    }
+
+    #[test]
+    fn test_signed_division() {
+        let mode = NumMode::new(Width::W16, true);
+        let minus_seven = mode.sub(0, 7).unwrap();
+
+        assert_eq!(mode.sign_extend(mode.div(minus_seven, 2).unwrap()), -3);
+        assert_eq!(mode.sign_extend(mode.rem(minus_seven, 2)), -1);
+        assert_eq!(NumMode::new(Width::W16, false).div(minus_seven, 2), Ok(0x7ffc));
+    }
+
+    #[test]
+    fn test_fits_checks_width() {
+        let byte = NumMode::new(Width::W8, true);
+        assert!(byte.fits(0xff));
+        assert!(!byte.fits(0x100));
+        assert!(NumMode::new(Width::W128, false).fits(NumType::MAX));
+    }
+
+    #[test]
+    fn test_signed_overflow() {
+        let mode = NumMode::new(Width::W32, true);
+        assert_eq!(mode.add(0x7fff_ffff, 1), Err(Overflow { wrapped: 0x8000_0000, positive: true }));
+        assert_eq!(mode.sub(0x8000_0000, 1), Err(Overflow { wrapped: 0x7fff_ffff, positive: false }));
+        assert_eq!(mode.mul(0x8000_0000, 0xffff_ffff), Err(Overflow { wrapped: 0x8000_0000, positive: true }));
+        assert_eq!(mode.div(0x8000_0000, 0xffff_ffff), Err(Overflow { wrapped: 0x8000_0000, positive: true }));
+        assert_eq!(mode.add(0xffff_ffff, 0xffff_ffff), Ok(0xffff_fffe));
+
+        let wide = NumMode::new(Width::W128, true);
+        assert_eq!(wide.add(wide.max(), 1), Err(Overflow { wrapped: wide.min(), positive: true }));
+        assert_eq!(wide.mul(wide.min(), 2), Err(Overflow { wrapped: 0, positive: false }));
+    }
+
+    #[test]
+    fn test_unsigned_overflow() {
+        let mode = NumMode::new(Width::W8, false);
+        assert_eq!(mode.add(0xff, 1), Err(Overflow { wrapped: 0, positive: true }));
+        assert_eq!(mode.sub(0, 1), Err(Overflow { wrapped: 0xff, positive: false }));
+        assert_eq!(mode.mul(0x10, 0x10), Err(Overflow { wrapped: 0, positive: true }));
+        assert_eq!(mode.mul(0x0f, 0x11), Ok(0xff));
+
+        let wide = NumMode::new(Width::W128, false);
+        assert_eq!(wide.add(NumType::MAX, 1), Err(Overflow { wrapped: 0, positive: true }));
+    }
+
+    #[test]
+    fn test_arithmetic_shift_left_overflow() {
+        let mode = NumMode::new(Width::W8, true);
+        assert_eq!(mode.arithmetic_shift_left(0x20, 1), Ok(0x40));
+        assert_eq!(mode.arithmetic_shift_left(0x40, 1), Err(Overflow { wrapped: 0x80, positive: true }));
+        assert_eq!(mode.arithmetic_shift_left(0xff, 7), Ok(0x80));
+        assert_eq!(mode.shift_left(0x40, 1), 0x80);
+
+        let wide = NumMode::new(Width::W128, true);
+        assert_eq!(wide.arithmetic_shift_left(NumType::MAX, 127), Ok(wide.min()));
+    }
+
+    #[test]
+    fn test_convert_between_modes() {
+        let byte = NumMode::new(Width::W8, true);
+        let word = NumMode::new(Width::W32, true);
+        let unsigned_byte = NumMode::new(Width::W8, false);
+
+        assert_eq!(word.convert(&Value::new(0xff, byte, false)), Ok(0xffff_ffff));
+        assert_eq!(byte.convert(&Value::new(0xffff_ff80, word, false)), Ok(0x80));
+        assert_eq!(byte.convert(&Value::new(200, word, false)), Err(Overflow { wrapped: 200, positive: true }));
+        assert_eq!(unsigned_byte.convert(&Value::new(0xff, byte, false)), Err(Overflow { wrapped: 0xff, positive: false }));
+        assert_eq!(byte.convert(&Value::new(0x7f, unsigned_byte, false)), Ok(0x7f));
+    }
+
+    #[test]
+    fn test_pow_overflow() {
+        let byte = NumMode::new(Width::W8, true);
+
+        assert_eq!(byte.pow(2, 6), Ok(64));
+        assert_eq!(byte.pow(0xfe, 7), Ok(0x80));
+        assert_eq!(byte.pow(2, 7), Err(Overflow { wrapped: 0x80, positive: true }));
+        assert_eq!(byte.pow(0xfe, 9), Err(Overflow { wrapped: 0, positive: false }));
+        assert_eq!(byte.pow(0xff, NumType::MAX), Ok(0xff));
+        assert_eq!(NumMode::new(Width::W8, false).pow(2, 1 << 40), Err(Overflow { wrapped: 0, positive: true }));
+        assert_eq!(NumMode::new(Width::W128, false).pow(3, 0), Ok(1));
+    }
+
+    #[test]
+    fn test_rotate_through_carry() {
+        let byte = NumMode::new(Width::W8, false);
+
+        assert_eq!(byte.rotate_left_through_carry(0x81, 1, false), (0x02, true));
+        assert_eq!(byte.rotate_left_through_carry(0x81, 1, true), (0x03, true));
+        assert_eq!(byte.rotate_right_through_carry(0x81, 1, false), (0x40, true));
+        assert_eq!(byte.rotate_right_through_carry(0x40, 1, true), (0xa0, false));
+        assert_eq!(byte.rotate_left_through_carry(0x81, 9, false), (0x81, false));
+        assert_eq!(byte.rotate_left_through_carry(0x81, 8, true), (0xc0, true));
+
+        let wide = NumMode::new(Width::W128, false);
+        assert_eq!(wide.rotate_left_through_carry(1 << 127, 1, false), (0, true));
+        assert_eq!(wide.rotate_right_through_carry(1, 1, true), (1 << 127, true));
+        assert_eq!(wide.rotate_left(1 << 127, 1), 1);
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        let q7 = NumMode::from_q_notation("q7").unwrap();
+
+        assert_eq!(q7.from_real(0.5), Ok(0x40));
+        assert_eq!(q7.from_real(-1.0), Ok(0x80));
+        assert_eq!(q7.from_real(1.0), Err(Overflow { wrapped: 0x80, positive: true }));
+        // 0.75 * 0.0078125 rounds to one raw unit
+        assert_eq!(q7.mul(0x60, 0x01), Ok(0x01));
+        assert_eq!(q7.div(0x20, 0x40), Ok(0x40));
+        assert_eq!(Value::new(0xff, q7, false).to_string(), "-0.0078125");
+        assert_eq!(Value::new(0x0324, NumMode::from_q_notation("uq8.8").unwrap(), false).to_string(), "3.140625");
+
+        assert_eq!(NumMode::from_q_notation("q16.16").map(|mode| mode.to_string()).as_deref(), Some("q16.16 (overflow: error)"));
+        assert_eq!(NumMode::from_q_notation("q1.16"), None);
+        assert_eq!(NumMode::from_q_notation("q64.64"), None);
+        assert_eq!(NumMode::from_q_notation("uq0.8").map(|mode| mode.format()), Some(NumFormat::Fixed { fraction_bits: 8 }));
+    }
+}
@@ -0,0 +1,299 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::num::IntErrorKind;
+
+use super::big_int::BigInt;
+use super::enums::operator::Operator;
+use super::functions::{Builtin, FunctionError};
+use super::num_type::{NumFormat, NumMode, NumType, Overflow, OverflowPolicy, Value};
+
+// Why an operation has no result. Each backend decides which operations it supports.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ArithmeticError<N = NumType> {
+    Overflow(Overflow<N>),
+    DivisionByZero,
+    NegativeExponent,
+    FractionalExponent,
+    InvalidShift,
+    Unsupported
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LiteralError {
+    Invalid,
+    Overflow
+}
+
+pub type Outcome<N> = Result<N, ArithmeticError<N>>;
+
+// A representation of numbers the evaluator works on. The mode holds what the number alone does
+// not tell, such as the width, the signedness and the overflow policy.
+pub trait Numeric: Sized + Clone + PartialEq + fmt::Debug + fmt::Display {
+    type Mode: Copy + Eq + fmt::Debug + fmt::Display;
+
+    // Digits of the radix without underscores. Decimal literals may have a fraction or an exponent,
+    // and a minus when the literal is negated where it is written, so the most negative number
+    // can be written. Other radixes are read as bit patterns.
+    fn parse(digits: &str, radix: u32, mode: Self::Mode) -> Result<Self, LiteralError>;
+    // Brings a value computed in another mode into this one
+    fn convert(value: &Value<Self>, mode: Self::Mode) -> Result<Self, Overflow<Self>>;
+    fn from_bool(value: bool, mode: Self::Mode) -> Self;
+    fn is_zero(&self, mode: Self::Mode) -> bool;
+    fn compare(&self, other: &Self, mode: Self::Mode) -> Ordering;
+
+    fn neg(&self, mode: Self::Mode) -> Outcome<Self>;
+    fn not(&self, mode: Self::Mode) -> Outcome<Self>;
+    fn add(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn sub(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn mul(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn div(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn rem(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn pow(&self, exponent: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn and(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn or(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn xor(&self, other: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn lsl(&self, amount: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn lsr(&self, amount: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn asl(&self, amount: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn asr(&self, amount: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn rol(&self, amount: &Self, mode: Self::Mode) -> Outcome<Self>;
+    fn ror(&self, amount: &Self, mode: Self::Mode) -> Outcome<Self>;
+    // Rotations through the carry give the rotated value and the new carry
+    fn rcl(&self, amount: &Self, carry: bool, mode: Self::Mode) -> Result<(Self, bool), ArithmeticError<Self>>;
+    fn rcr(&self, amount: &Self, carry: bool, mode: Self::Mode) -> Result<(Self, bool), ArithmeticError<Self>>;
+
+    // Backends without their own builtins get those needing only arithmetic and comparisons
+    fn apply(builtin: &Builtin, args: &[Self], mode: Self::Mode) -> Result<Self, FunctionError<Self>> {
+        builtin.apply_numeric(args, mode)
+    }
+
+    fn overflow_policy(mode: Self::Mode) -> OverflowPolicy;
+    // What an overflowing result saturates to
+    fn saturated(positive: bool, mode: Self::Mode) -> Self;
+    // Names the number format in error messages
+    fn format_name(mode: Self::Mode) -> &'static str;
+    // Whether the mode has negative numbers, for backends with them to say so
+    fn is_signed(_mode: Self::Mode) -> bool {
+        false
+    }
+    // How the value reads in the mode, used to print a Value
+    fn fmt_value(&self, mode: Self::Mode, f: &mut fmt::Formatter) -> fmt::Result;
+    // The raw bits as a non-negative number, and how many of them the bases show
+    fn bit_pattern(&self, mode: Self::Mode) -> (BigInt, u32);
+
+    // Bits of the magnitude, for the backends whose numbers grow without bound. Numbers of a
+    // fixed width keep the default and are never checked against EvalLimits.
+    fn magnitude_bits(&self) -> Option<u64> {
+        None
+    }
+
+    // A lower bound on the bits of the magnitude of the result, known before computing it, for
+    // the operators whose results can grow far past their operands
+    fn result_bits(_op: Operator, _left: &Self, _right: &Self, _mode: Self::Mode) -> Option<u64> {
+        None
+    }
+}
+
+// Floats have no bits to work on, and fixed-point powers would need a whole exponent
+fn require_bits(mode: NumMode) -> Result<(), ArithmeticError> {
+    match mode.format() {
+        NumFormat::Float => Err(ArithmeticError::Unsupported),
+        _ => Ok(())
+    }
+}
+
+// Rotation amounts are whole numbers even in the fixed-point modes
+fn rotation_amount(amount: NumType, mode: NumMode) -> Result<NumType, ArithmeticError> {
+    require_bits(mode)?;
+    mode.count(amount).ok_or(ArithmeticError::InvalidShift)
+}
+
+fn shift_amount(amount: NumType, mode: NumMode) -> Result<NumType, ArithmeticError> {
+    match rotation_amount(amount, mode)? {
+        amount if amount < mode.bits() as NumType => Ok(amount),
+        _ => Err(ArithmeticError::InvalidShift)
+    }
+}
+
+fn require_divisor(divisor: NumType, mode: NumMode) -> Result<(), ArithmeticError> {
+    match mode.is_zero(divisor) {
+        true => Err(ArithmeticError::DivisionByZero),
+        false => Ok(())
+    }
+}
+
+// Integers, floats and fixed-point numbers of up to 128 bits, told apart by the mode
+impl Numeric for NumType {
+    type Mode = NumMode;
+
+    // Decimal numbers are real numbers in the float and fixed-point modes, while prefixed and
+    // suffixed numbers are integers, giving the raw bit pattern of a fixed-point value
+    fn parse(digits: &str, radix: u32, mode: NumMode) -> Result<NumType, LiteralError> {
+        match (mode.format(), radix) {
+            (NumFormat::Integer, _) | (NumFormat::Fixed { .. }, 2 | 8 | 16) => {},
+            (NumFormat::Float, 2 | 8 | 16) => {
+                let integer = NumType::from_str_radix(digits, radix).map_err(|err| match err.kind() {
+                    IntErrorKind::PosOverflow => LiteralError::Overflow,
+                    _ => LiteralError::Invalid
+                })?;
+                return mode.from_real(integer as f64).map_err(|_| LiteralError::Overflow);
+            },
+            _ => {
+                let real = digits.parse::<f64>().map_err(|_| LiteralError::Invalid)?;
+                return mode.from_real(real).map_err(|_| LiteralError::Overflow);
+            }
+        }
+
+        let (negative, digits) = match (radix, digits.strip_prefix('-')) {
+            (10, Some(digits)) => (true, digits),
+            _ => (false, digits)
+        };
+        let num = match NumType::from_str_radix(digits, radix) {
+            Ok(num) => num,
+            Err(err) if *err.kind() == IntErrorKind::PosOverflow => return Err(LiteralError::Overflow),
+            Err(_) => return Err(LiteralError::Invalid)
+        };
+
+        // Decimal literals are numbers, checked against the range of the mode
+        match (radix, negative) {
+            (10, false) if num <= mode.max() => Ok(num),
+            (10, true) if num == 0 || (mode.is_signed() && num <= mode.min()) => Ok(mode.truncate(num.wrapping_neg())),
+            (10, _) => Err(LiteralError::Overflow),
+            _ if mode.fits(num) => Ok(num),
+            _ => Err(LiteralError::Overflow)
+        }
+    }
+
+    fn convert(value: &Value, mode: NumMode) -> Result<NumType, Overflow> {
+        mode.convert(value)
+    }
+
+    fn from_bool(value: bool, mode: NumMode) -> NumType {
+        mode.from_bool(value)
+    }
+
+    fn is_zero(&self, mode: NumMode) -> bool {
+        mode.is_zero(*self)
+    }
+
+    fn compare(&self, other: &NumType, mode: NumMode) -> Ordering {
+        mode.compare(*self, *other)
+    }
+
+    fn neg(&self, mode: NumMode) -> Outcome<NumType> {
+        mode.sub(0, *self).map_err(ArithmeticError::Overflow)
+    }
+
+    fn not(&self, mode: NumMode) -> Outcome<NumType> {
+        require_bits(mode)?;
+        Ok(mode.not(*self))
+    }
+
+    fn add(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        mode.add(*self, *other).map_err(ArithmeticError::Overflow)
+    }
+
+    fn sub(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        mode.sub(*self, *other).map_err(ArithmeticError::Overflow)
+    }
+
+    fn mul(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        mode.mul(*self, *other).map_err(ArithmeticError::Overflow)
+    }
+
+    fn div(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        require_divisor(*other, mode)?;
+        mode.div(*self, *other).map_err(ArithmeticError::Overflow)
+    }
+
+    fn rem(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        require_divisor(*other, mode)?;
+        Ok(mode.rem(*self, *other))
+    }
+
+    fn pow(&self, exponent: &NumType, mode: NumMode) -> Outcome<NumType> {
+        match mode.format() {
+            NumFormat::Integer if mode.is_signed() && mode.sign_extend(*exponent) < 0 => return Err(ArithmeticError::NegativeExponent),
+            NumFormat::Float if mode.to_real(*self) < 0.0 && mode.to_real(*exponent).fract() != 0.0 => return Err(ArithmeticError::FractionalExponent),
+            NumFormat::Fixed { .. } => return Err(ArithmeticError::Unsupported),
+            _ => {}
+        }
+
+        mode.pow(*self, *exponent).map_err(ArithmeticError::Overflow)
+    }
+
+    fn and(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        require_bits(mode)?;
+        Ok(self & other)
+    }
+
+    fn or(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        require_bits(mode)?;
+        Ok(self | other)
+    }
+
+    fn xor(&self, other: &NumType, mode: NumMode) -> Outcome<NumType> {
+        require_bits(mode)?;
+        Ok(self ^ other)
+    }
+
+    fn lsl(&self, amount: &NumType, mode: NumMode) -> Outcome<NumType> {
+        Ok(mode.shift_left(*self, shift_amount(*amount, mode)?))
+    }
+
+    fn lsr(&self, amount: &NumType, mode: NumMode) -> Outcome<NumType> {
+        Ok(mode.logical_shift_right(*self, shift_amount(*amount, mode)?))
+    }
+
+    fn asl(&self, amount: &NumType, mode: NumMode) -> Outcome<NumType> {
+        mode.arithmetic_shift_left(*self, shift_amount(*amount, mode)?).map_err(ArithmeticError::Overflow)
+    }
+
+    fn asr(&self, amount: &NumType, mode: NumMode) -> Outcome<NumType> {
+        Ok(mode.arithmetic_shift_right(*self, shift_amount(*amount, mode)?))
+    }
+
+    fn rol(&self, amount: &NumType, mode: NumMode) -> Outcome<NumType> {
+        Ok(mode.rotate_left(*self, rotation_amount(*amount, mode)?))
+    }
+
+    fn ror(&self, amount: &NumType, mode: NumMode) -> Outcome<NumType> {
+        Ok(mode.rotate_right(*self, rotation_amount(*amount, mode)?))
+    }
+
+    fn rcl(&self, amount: &NumType, carry: bool, mode: NumMode) -> Result<(NumType, bool), ArithmeticError> {
+        Ok(mode.rotate_left_through_carry(*self, rotation_amount(*amount, mode)?, carry))
+    }
+
+    fn rcr(&self, amount: &NumType, carry: bool, mode: NumMode) -> Result<(NumType, bool), ArithmeticError> {
+        Ok(mode.rotate_right_through_carry(*self, rotation_amount(*amount, mode)?, carry))
+    }
+
+    fn apply(builtin: &Builtin, args: &[NumType], mode: NumMode) -> Result<NumType, FunctionError> {
+        builtin.apply(args, mode)
+    }
+
+    fn overflow_policy(mode: NumMode) -> OverflowPolicy {
+        mode.overflow_policy()
+    }
+
+    fn saturated(positive: bool, mode: NumMode) -> NumType {
+        if positive { mode.max() } else { mode.min() }
+    }
+
+    fn format_name(mode: NumMode) -> &'static str {
+        mode.format().name()
+    }
+
+    fn is_signed(mode: NumMode) -> bool {
+        mode.is_signed()
+    }
+
+    fn fmt_value(&self, mode: NumMode, f: &mut fmt::Formatter) -> fmt::Result {
+        mode.fmt_value(*self, f)
+    }
+
+    fn bit_pattern(&self, mode: NumMode) -> (BigInt, u32) {
+        (BigInt::from(*self), mode.bits())
+    }
+}
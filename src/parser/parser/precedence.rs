@@ -0,0 +1,235 @@
+use std::fmt;
+
+use super::enums::operator::Operator;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Associativity {
+    Left,
+    Right
+}
+
+type Entry = (Operator, u8, Associativity);
+
+// Binding strength of the binary operators, higher binds tighter, starting at 1. The prefix
+// operators get their own level, below the power so that -2 ** 2 is -(2 ** 2).
+struct Preset {
+    entries: [Entry; 25],
+    prefix: u8
+}
+
+// Comparisons bind looser than the bitwise operators, so `x and 0xf == 3` compares the masked x
+const DEFAULT: Preset = Preset {
+    entries: [
+        (Operator::Pow, 11, Associativity::Right),
+        (Operator::Lsl, 9, Associativity::Left),
+        (Operator::Lsr, 9, Associativity::Left),
+        (Operator::Asl, 9, Associativity::Left),
+        (Operator::Asr, 9, Associativity::Left),
+        (Operator::Rol, 9, Associativity::Left),
+        (Operator::Ror, 9, Associativity::Left),
+        (Operator::Rcl, 9, Associativity::Left),
+        (Operator::Rcr, 9, Associativity::Left),
+        (Operator::Mul, 8, Associativity::Left),
+        (Operator::Div, 8, Associativity::Left),
+        (Operator::Mod, 8, Associativity::Left),
+        (Operator::Add, 7, Associativity::Left),
+        (Operator::Sub, 7, Associativity::Left),
+        (Operator::And, 6, Associativity::Left),
+        (Operator::Xor, 5, Associativity::Left),
+        (Operator::Or, 4, Associativity::Left),
+        (Operator::Eq, 3, Associativity::Left),
+        (Operator::Ne, 3, Associativity::Left),
+        (Operator::Lt, 3, Associativity::Left),
+        (Operator::Le, 3, Associativity::Left),
+        (Operator::Gt, 3, Associativity::Left),
+        (Operator::Ge, 3, Associativity::Left),
+        (Operator::LogicalAnd, 2, Associativity::Left),
+        (Operator::LogicalOr, 1, Associativity::Left)
+    ],
+    prefix: 10
+};
+
+// Shifts bind looser than the arithmetic, as in `1 << 2 + 3` being `1 << 5`, and the bitwise
+// operators looser than the comparisons, as in `x & 0xf == 3` being `x & 1`
+const C: Preset = Preset {
+    entries: [
+        (Operator::Pow, 12, Associativity::Right),
+        (Operator::Mul, 10, Associativity::Left),
+        (Operator::Div, 10, Associativity::Left),
+        (Operator::Mod, 10, Associativity::Left),
+        (Operator::Add, 9, Associativity::Left),
+        (Operator::Sub, 9, Associativity::Left),
+        (Operator::Lsl, 8, Associativity::Left),
+        (Operator::Lsr, 8, Associativity::Left),
+        (Operator::Asl, 8, Associativity::Left),
+        (Operator::Asr, 8, Associativity::Left),
+        (Operator::Rol, 8, Associativity::Left),
+        (Operator::Ror, 8, Associativity::Left),
+        (Operator::Rcl, 8, Associativity::Left),
+        (Operator::Rcr, 8, Associativity::Left),
+        (Operator::Lt, 7, Associativity::Left),
+        (Operator::Le, 7, Associativity::Left),
+        (Operator::Gt, 7, Associativity::Left),
+        (Operator::Ge, 7, Associativity::Left),
+        (Operator::Eq, 6, Associativity::Left),
+        (Operator::Ne, 6, Associativity::Left),
+        (Operator::And, 5, Associativity::Left),
+        (Operator::Xor, 4, Associativity::Left),
+        (Operator::Or, 3, Associativity::Left),
+        (Operator::LogicalAnd, 2, Associativity::Left),
+        (Operator::LogicalOr, 1, Associativity::Left)
+    ],
+    prefix: 11
+};
+
+// Like C for the arithmetic, but every comparison shares a level below the bitwise operators
+const PYTHON: Preset = Preset {
+    entries: [
+        (Operator::Pow, 11, Associativity::Right),
+        (Operator::Mul, 9, Associativity::Left),
+        (Operator::Div, 9, Associativity::Left),
+        (Operator::Mod, 9, Associativity::Left),
+        (Operator::Add, 8, Associativity::Left),
+        (Operator::Sub, 8, Associativity::Left),
+        (Operator::Lsl, 7, Associativity::Left),
+        (Operator::Lsr, 7, Associativity::Left),
+        (Operator::Asl, 7, Associativity::Left),
+        (Operator::Asr, 7, Associativity::Left),
+        (Operator::Rol, 7, Associativity::Left),
+        (Operator::Ror, 7, Associativity::Left),
+        (Operator::Rcl, 7, Associativity::Left),
+        (Operator::Rcr, 7, Associativity::Left),
+        (Operator::And, 6, Associativity::Left),
+        (Operator::Xor, 5, Associativity::Left),
+        (Operator::Or, 4, Associativity::Left),
+        (Operator::Eq, 3, Associativity::Left),
+        (Operator::Ne, 3, Associativity::Left),
+        (Operator::Lt, 3, Associativity::Left),
+        (Operator::Le, 3, Associativity::Left),
+        (Operator::Gt, 3, Associativity::Left),
+        (Operator::Ge, 3, Associativity::Left),
+        (Operator::LogicalAnd, 2, Associativity::Left),
+        (Operator::LogicalOr, 1, Associativity::Left)
+    ],
+    prefix: 10
+};
+
+// Precedence and associativity of every binary operator, consulted by the syntax analyzer
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PrecedenceTable {
+    entries: Vec<Entry>,
+    prefix: u8
+}
+
+impl Default for PrecedenceTable {
+    fn default() -> PrecedenceTable {
+        PrecedenceTable::from_preset(&DEFAULT)
+    }
+}
+
+impl PrecedenceTable {
+    pub fn c() -> PrecedenceTable {
+        PrecedenceTable::from_preset(&C)
+    }
+
+    pub fn python() -> PrecedenceTable {
+        PrecedenceTable::from_preset(&PYTHON)
+    }
+
+    fn from_preset(preset: &Preset) -> PrecedenceTable {
+        PrecedenceTable { entries: preset.entries.to_vec(), prefix: preset.prefix }
+    }
+
+    pub fn from_name(preset: &str) -> Option<PrecedenceTable> {
+        match preset {
+            "default" => Some(PrecedenceTable::default()),
+            "c" => Some(PrecedenceTable::c()),
+            "python" => Some(PrecedenceTable::python()),
+            _ => None
+        }
+    }
+
+    // Overrides the level and associativity of one operator. Levels start at 1.
+    pub fn with(mut self, operator: Operator, level: u8, associativity: Associativity) -> PrecedenceTable {
+        assert!(level > 0, "precedence levels start at 1");
+
+        self.entries.retain(|(entry_operator, _, _)| *entry_operator != operator);
+        self.entries.push((operator, level, associativity));
+        self
+    }
+
+    // Binary operators above this level bind tighter than the prefix operators
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    // None for operators that are not binary
+    pub fn get(&self, operator: Operator) -> Option<(u8, Associativity)> {
+        self.entries.iter()
+            .find(|(entry_operator, _, _)| *entry_operator == operator)
+            .map(|(_, level, associativity)| (*level, *associativity))
+    }
+}
+
+// One line per level, from the tightest binding: `6  lsl lsr asl asr  left`
+impl fmt::Display for PrecedenceTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut entries = self.entries.clone();
+        entries.sort_by_key(|(_, level, _)| std::cmp::Reverse(*level));
+
+        let mut lines: Vec<(u8, String)> = entries.chunk_by(|left, right| left.1 == right.1 && left.2 == right.2)
+            .map(|chunk| {
+                let operators: Vec<String> = chunk.iter().map(|(operator, _, _)| operator.to_string()).collect();
+                let associativity = if chunk[0].2 == Associativity::Left { "left" } else { "right" };
+                (chunk[0].1, format!("{:<2} {}  {}", chunk[0].1, operators.join(" "), associativity))
+            })
+            .collect();
+
+        let prefix_line = lines.iter().position(|(level, _)| *level <= self.prefix).unwrap_or(lines.len());
+        lines.insert(prefix_line, (self.prefix, format!("{:<2} - not !  prefix", self.prefix)));
+
+        let lines: Vec<String> = lines.into_iter().map(|(_, line)| line).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(PrecedenceTable::default().get(Operator::Lsl), Some((9, Associativity::Left)));
+        assert_eq!(PrecedenceTable::c().get(Operator::Lsl), Some((8, Associativity::Left)));
+        assert_eq!(PrecedenceTable::python().get(Operator::Lt), Some((3, Associativity::Left)));
+        assert_eq!(PrecedenceTable::c().get(Operator::Not), None);
+        assert_eq!(PrecedenceTable::from_name("python"), Some(PrecedenceTable::python()));
+    }
+
+    #[test]
+    fn test_override() {
+        let table = PrecedenceTable::default().with(Operator::Sub, 9, Associativity::Right);
+
+        assert_eq!(table.get(Operator::Sub), Some((9, Associativity::Right)));
+        assert_eq!(table.get(Operator::Add), Some((7, Associativity::Left)));
+    }
+
+    #[test]
+    fn test_display() {
+        let table = PrecedenceTable::python().with(Operator::LogicalOr, 1, Associativity::Right);
+
+        assert_eq!(table.to_string(), [
+            "11 **  right",
+            "10 - not !  prefix",
+            "9  * / %  left",
+            "8  + -  left",
+            "7  lsl lsr asl asr rol ror rcl rcr  left",
+            "6  and  left",
+            "5  xor  left",
+            "4  or  left",
+            "3  == != < <= > >=  left",
+            "2  &&  left",
+            "1  ||  right"
+        ].join("\n"));
+    }
+}
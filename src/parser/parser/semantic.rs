@@ -1,135 +1,582 @@
-use super::{enums::element::Element, structs::operator::Operator, NumType};
-use super::enums::element::Operator as Op;
+use super::enums::expr::Expr;
+use super::enums::operator::{Operator, UnaryOperator};
+use super::error::ParseError;
+use super::functions::{Builtin, FunctionError};
+use super::limits::EvalLimits;
+use super::num_type::{Overflow, OverflowPolicy, Value};
+use super::numeric::{ArithmeticError, Numeric};
+use super::structs::environment::Environment;
+use super::structs::span::Span;
 
-pub struct SemanticAnalyzer {
-    elements: Vec<Vec<Element<NumType>>>
+pub struct SemanticAnalyzer<N: Numeric> {
+    expression: Expr<N>,
+    mode: N::Mode,
+    limits: EvalLimits,
+    overflowed: bool
 }
 
-impl SemanticAnalyzer {
-    pub fn new(elements: Vec<Vec<Element<NumType>>>) -> SemanticAnalyzer {
+impl<N: Numeric> SemanticAnalyzer<N> {
+    pub fn new(expression: Expr<N>, mode: N::Mode) -> SemanticAnalyzer<N> {
         SemanticAnalyzer {
-            elements
+            expression,
+            mode,
+            limits: EvalLimits::default(),
+            overflowed: false
         }
     }
 
-    pub fn calculate(&mut self) -> NumType {
-        let mut sub_expr_values = vec![None; self.elements.len()];
-
-        let mut expression_iter = self.elements.iter_mut().enumerate().rev();
-        while let Some((expression_index, mut expression)) = expression_iter.next() {
-            let mut operator_iter = expression.iter().enumerate();
-
-            let mut first_priority_operators: Vec<Operator> = Vec::new();
-            let mut second_priority_operators: Vec<Operator> = Vec::new();
-            let mut third_priority_operators: Vec<Operator> = Vec::new();
-            let mut fourth_priority_operators: Vec<Operator> = Vec::new();
-            let mut fifth_priority_operators: Vec<Operator> = Vec::new();
-            let mut sixth_priority_operators: Vec<Operator> = Vec::new();
-            let mut seventh_priority_operators: Vec<Operator> = Vec::new();
-
-            while let Some((element_index, element)) = operator_iter.next() {
-                match element {
-                    Element::Operator(operator) => {
-                        match operator {
-                            Op::Not => {
-                                first_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                            Op::Lsl | Op::Lsr | Op::Asl | Op::Asr => {
-                                second_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                            Op::Mul | Op::Div | Op::Mod => {
-                                third_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                            Op::Add | Op::Sub => {
-                                fourth_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                            Op::And => {
-                                fifth_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                            Op::Xor => {
-                                sixth_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                            Op::Or => {
-                                seventh_priority_operators.push(Operator::new(element_index, *operator));
-                            },
-                        }
-                    },
-                    _ => {}
+    pub fn with_limits(self, limits: EvalLimits) -> SemanticAnalyzer<N> {
+        SemanticAnalyzer { limits, ..self }
+    }
+
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+
+    // Variables are read from the environment, and assignments are stored into it
+    pub fn calculate(&mut self, environment: &mut Environment<N>) -> Result<N, ParseError> {
+        let node = lower(&self.expression)?;
+        let mut evaluation = Evaluation::new(self.limits);
+        let result = evaluate(&node, self.mode, environment, &mut evaluation);
+
+        self.overflowed = evaluation.overflowed;
+        result
+    }
+}
+
+// What one evaluation keeps track of besides its result: whether it overflowed, and how much of
+// the limits it has used
+pub(super) struct Evaluation {
+    limits: EvalLimits,
+    operations: usize,
+    call_depth: usize,
+    pub(super) overflowed: bool
+}
+
+impl Evaluation {
+    pub(super) fn new(limits: EvalLimits) -> Evaluation {
+        Evaluation { limits, operations: 0, call_depth: 0, overflowed: false }
+    }
+
+    fn count_operation(&mut self, span: Span) -> Result<(), ParseError> {
+        if self.operations >= self.limits.max_operations() {
+            return Err(ParseError::TooManyOperations { limit: self.limits.max_operations(), span });
+        }
+
+        self.operations += 1;
+        Ok(())
+    }
+
+    fn check_bits(&self, bits: Option<u64>, span: Span) -> Result<(), ParseError> {
+        match bits {
+            Some(bits) if bits > self.limits.max_big_int_bits() => Err(ParseError::NumberTooLarge { limit: self.limits.max_big_int_bits(), span }),
+            _ => Ok(())
+        }
+    }
+}
+
+// Where an evaluation reads and writes its variables and the carry flag
+pub(super) trait Scope<N: Numeric> {
+    fn get(&self, name: &str) -> Option<Value<N>>;
+    fn assign(&mut self, name: &str, value: Value<N>);
+    fn carry(&self) -> bool;
+    fn set_carry(&mut self, carry: bool);
+}
+
+impl<N: Numeric> Scope<N> for Environment<N> {
+    fn get(&self, name: &str) -> Option<Value<N>> {
+        Environment::get(self, name)
+    }
+
+    fn assign(&mut self, name: &str, value: Value<N>) {
+        Environment::set(self, name, value)
+    }
+
+    fn carry(&self) -> bool {
+        Environment::carry(self)
+    }
+
+    fn set_carry(&mut self, carry: bool) {
+        Environment::set_carry(self, carry)
+    }
+}
+
+// The form an expression is evaluated in. Each run of binary operators is one chain evaluated in
+// a loop, so recursion follows the nesting of the input rather than its length.
+// Functions are looked up once, when lowering.
+#[derive(Debug, Clone)]
+pub(super) enum Node<N> {
+    Literal(N),
+    Variable { name: String, span: Span },
+    Call { builtin: &'static Builtin, name_span: Span, args: Vec<(Node<N>, Span)>, span: Span },
+    Assign { name: String, value: Box<Node<N>> },
+    Unary { op: UnaryOperator, operand: Box<Node<N>>, span: Span },
+    Conditional { condition: Box<Node<N>>, if_true: Box<Node<N>>, if_false: Box<Node<N>> },
+    Chain { first: Box<Node<N>>, links: Vec<Link<N>> }
+}
+
+// An operator of a chain along with its right operand
+#[derive(Debug, Clone)]
+pub(super) struct Link<N> {
+    op: Operator,
+    op_span: Span,
+    operand: Node<N>
+}
+
+impl<N> Node<N> {
+    // Names of the variables read, in the order they first appear
+    pub(super) fn collect_variables(&self, variables: &mut Vec<String>) {
+        match self {
+            Node::Literal(_) => {},
+            Node::Variable { name, .. } => {
+                if !variables.contains(name) {
+                    variables.push(name.clone());
                 }
+            },
+            Node::Call { args, .. } => args.iter().for_each(|(arg, _)| arg.collect_variables(variables)),
+            Node::Assign { value, .. } => value.collect_variables(variables),
+            Node::Unary { operand, .. } => operand.collect_variables(variables),
+            Node::Conditional { condition, if_true, if_false } => {
+                condition.collect_variables(variables);
+                if_true.collect_variables(variables);
+                if_false.collect_variables(variables);
+            },
+            Node::Chain { first, links } => {
+                first.collect_variables(variables);
+                links.iter().for_each(|link| link.operand.collect_variables(variables));
             }
+        }
+    }
+}
 
-            let ordered_priority_operators = [
-                &mut first_priority_operators,
-                &mut second_priority_operators,
-                &mut third_priority_operators,
-                &mut fourth_priority_operators,
-                &mut fifth_priority_operators,
-                &mut sixth_priority_operators,
-                &mut seventh_priority_operators
-            ];
-
-            for i in 0..ordered_priority_operators.len() {
-                for j in 0..ordered_priority_operators[i].len() {
-                    let deleted_elements = {
-                        let operator = &ordered_priority_operators[i][j];
-                        operator.execute(&mut expression, &sub_expr_values)
-                    };
-            
-                    if let Some(deleted_elements) = deleted_elements {
-                        let current_operator_index = ordered_priority_operators[i][j].get_index();
-            
-                        for k in 0..ordered_priority_operators.len() {
-                            for l in 0..ordered_priority_operators[k].len() {
-                                if ordered_priority_operators[k][l].get_index() > current_operator_index {
-                                    let new_index = ordered_priority_operators[k][l].get_index() - deleted_elements;
-                                    ordered_priority_operators[k][l].set_index(
-                                        new_index
-                                    );
-                                }
-                            }
-                        }
-                    }
-                }
+pub(super) fn lower<N: Numeric>(expression: &Expr<N>) -> Result<Node<N>, ParseError> {
+    match expression {
+        Expr::Literal { value, .. } => Ok(Node::Literal(value.clone())),
+        Expr::Group { inner, .. } => lower(inner),
+        Expr::Variable { name, span } => Ok(Node::Variable { name: name.clone(), span: *span }),
+        Expr::Call { name, name_span, args, span } => {
+            let builtin = Builtin::find(name).ok_or_else(|| ParseError::UnknownFunction { name: name.clone(), span: *name_span })?;
+            if !builtin.arity.accepts(args.len()) {
+                return Err(ParseError::ArgumentCount { name: name.clone(), expected: builtin.arity, found: args.len(), span: *span });
             }
 
-            if let Element::Number(val) = expression[0] {
-                sub_expr_values[expression_index] = Some(val);
+            let args = args.iter()
+                .map(|arg| Ok((lower(arg)?, arg.span())))
+                .collect::<Result<Vec<(Node<N>, Span)>, ParseError>>()?;
+            Ok(Node::Call { builtin, name_span: *name_span, args, span: *span })
+        },
+        Expr::Assign { name, value, .. } => Ok(Node::Assign { name: name.clone(), value: Box::new(lower(value)?) }),
+        Expr::Unary { op, operand, span } => Ok(Node::Unary { op: *op, operand: Box::new(lower(operand)?), span: *span }),
+        Expr::Conditional { condition, if_true, if_false, .. } => Ok(Node::Conditional {
+            condition: Box::new(lower(condition)?),
+            if_true: Box::new(lower(if_true)?),
+            if_false: Box::new(lower(if_false)?)
+        }),
+        Expr::Binary { first, links, .. } => {
+            let first = lower(first)?;
+            let links = links.iter()
+                .map(|link| Ok(Link { op: link.op, op_span: link.op_span, operand: lower(&link.operand)? }))
+                .collect::<Result<Vec<Link<N>>, ParseError>>()?;
+
+            Ok(Node::Chain { first: Box::new(first), links })
+        }
+    }
+}
+
+// Allocates nothing unless the backend does or evaluation fails
+pub(super) fn evaluate<N: Numeric, S: Scope<N>>(node: &Node<N>, mode: N::Mode, environment: &mut S, evaluation: &mut Evaluation) -> Result<N, ParseError> {
+    match node {
+        Node::Literal(value) => Ok(value.clone()),
+        Node::Variable { name, span } => {
+            let value = environment.get(name).ok_or_else(|| ParseError::UndefinedVariable { name: name.clone(), span: *span })?;
+
+            // Variables may hold numbers computed under looser limits
+            let result = match N::convert(&value, mode) {
+                Ok(result) => result,
+                Err(overflow) => handle_overflow(overflow, *span, mode, &mut evaluation.overflowed)?
+            };
+            evaluation.check_bits(result.magnitude_bits(), *span)?;
+
+            Ok(result)
+        },
+        Node::Call { builtin, name_span, args, span } => {
+            if evaluation.call_depth >= evaluation.limits.max_call_depth() {
+                return Err(ParseError::CallsTooDeep { limit: evaluation.limits.max_call_depth(), span: *name_span });
             }
-            
-        };
 
-        return sub_expr_values[0].unwrap();
+            evaluation.call_depth += 1;
+            let result = evaluate_call(builtin, *name_span, args, *span, mode, environment, evaluation);
+            evaluation.call_depth -= 1;
+
+            result
+        },
+        Node::Assign { name, value } => {
+            let result = evaluate(value, mode, environment, evaluation)?;
+
+            environment.assign(name, Value::from_raw(result.clone(), mode, false));
+            Ok(result)
+        },
+        Node::Unary { op, operand, span } => {
+            let operand = evaluate(operand, mode, environment, evaluation)?;
+            evaluation.count_operation(*span)?;
+
+            let result = match op {
+                UnaryOperator::Not => check(operand.not(mode), Operator::Not, *span, mode, &mut evaluation.overflowed)?,
+                UnaryOperator::LogicalNot => N::from_bool(operand.is_zero(mode), mode),
+                UnaryOperator::Neg => check(operand.neg(mode), Operator::Sub, *span, mode, &mut evaluation.overflowed)?
+            };
+            evaluation.check_bits(result.magnitude_bits(), *span)?;
+
+            Ok(result)
+        },
+        Node::Conditional { condition, if_true, if_false } => {
+            match evaluate(condition, mode, environment, evaluation)?.is_zero(mode) {
+                true => evaluate(if_false, mode, environment, evaluation),
+                false => evaluate(if_true, mode, environment, evaluation)
+            }
+        },
+        Node::Chain { first, links } => {
+            let mut result = evaluate(first, mode, environment, evaluation)?;
+            for link in links {
+                result = evaluate_link(result, link, mode, environment, evaluation)?;
+            }
+
+            Ok(result)
+        }
+    }
+}
+
+fn evaluate_call<N: Numeric, S: Scope<N>>(builtin: &Builtin, name_span: Span, args: &[(Node<N>, Span)], span: Span, mode: N::Mode, environment: &mut S, evaluation: &mut Evaluation) -> Result<N, ParseError> {
+    let arg_span = |index: usize| args[index].1;
+    match args {
+        [(arg, _)] => {
+            let value = evaluate(arg, mode, environment, evaluation)?;
+            apply(builtin, &[value], mode, arg_span, name_span, span, evaluation)
+        },
+        [(left, _), (right, _)] => {
+            let left = evaluate(left, mode, environment, evaluation)?;
+            let right = evaluate(right, mode, environment, evaluation)?;
+            apply(builtin, &[left, right], mode, arg_span, name_span, span, evaluation)
+        },
+        // Only min and max take more arguments, folded two at a time
+        _ => {
+            let mut result = evaluate(&args[0].0, mode, environment, evaluation)?;
+            for (index, (arg, _)) in args.iter().enumerate().skip(1) {
+                let value = evaluate(arg, mode, environment, evaluation)?;
+                let arg_span = |pair_index: usize| args[if pair_index == 0 { 0 } else { index }].1;
+                result = apply(builtin, &[result, value], mode, arg_span, name_span, span, evaluation)?;
+            }
+
+            Ok(result)
+        }
+    }
+}
+
+fn evaluate_link<N: Numeric, S: Scope<N>>(left_operand: N, link: &Link<N>, mode: N::Mode, environment: &mut S, evaluation: &mut Evaluation) -> Result<N, ParseError> {
+    evaluation.count_operation(link.op_span)?;
+
+    match link.op {
+        // The right operand is only evaluated when the left one does not decide the result
+        Operator::LogicalAnd => match left_operand.is_zero(mode) {
+            true => Ok(N::from_bool(false, mode)),
+            false => Ok(N::from_bool(!evaluate(&link.operand, mode, environment, evaluation)?.is_zero(mode), mode))
+        },
+        Operator::LogicalOr => match left_operand.is_zero(mode) {
+            true => Ok(N::from_bool(!evaluate(&link.operand, mode, environment, evaluation)?.is_zero(mode), mode)),
+            false => Ok(N::from_bool(true, mode))
+        },
+        // Rotations through the carry read the carry flag and leave the bit rotated out in it
+        Operator::Rcl | Operator::Rcr => {
+            let right_operand = evaluate(&link.operand, mode, environment, evaluation)?;
+
+            let rotated = match link.op {
+                Operator::Rcl => left_operand.rcl(&right_operand, environment.carry(), mode),
+                _ => left_operand.rcr(&right_operand, environment.carry(), mode)
+            };
+
+            let (result, carry) = rotated.map_err(|error| to_parse_error(error, link.op, link.op_span, mode))?;
+            environment.set_carry(carry);
+            Ok(result)
+        },
+        // Results known to be too large are not computed, the others are checked once they are
+        op => {
+            let right_operand = evaluate(&link.operand, mode, environment, evaluation)?;
+            evaluation.check_bits(N::result_bits(op, &left_operand, &right_operand, mode), link.op_span)?;
+
+            let result = execute(op, link.op_span, &left_operand, &right_operand, mode, &mut evaluation.overflowed)?;
+            evaluation.check_bits(result.magnitude_bits(), link.op_span)?;
+
+            Ok(result)
+        }
+    }
+}
+
+// The power is the only function whose result can grow far past the bits of its arguments
+fn apply<N: Numeric>(builtin: &Builtin, values: &[N], mode: N::Mode, arg_span: impl Fn(usize) -> Span, name_span: Span, span: Span, evaluation: &mut Evaluation) -> Result<N, ParseError> {
+    evaluation.count_operation(span)?;
+    if let ("pow", [base, exponent]) = (builtin.name, values) {
+        evaluation.check_bits(N::result_bits(Operator::Pow, base, exponent, mode), span)?;
+    }
+
+    let result = match N::apply(builtin, values, mode) {
+        Ok(result) => Ok(result),
+        Err(FunctionError::Overflow(overflow)) => handle_overflow(overflow, span, mode, &mut evaluation.overflowed),
+        Err(FunctionError::InvalidArgument { index, reason }) => Err(ParseError::InvalidArgument { reason, span: arg_span(index) }),
+        Err(FunctionError::Unsupported) => Err(ParseError::UnsupportedFunction { name: builtin.name.to_string(), format: N::format_name(mode), span: name_span })
+    }?;
+    evaluation.check_bits(result.magnitude_bits(), span)?;
+
+    Ok(result)
+}
+
+fn execute<N: Numeric>(op: Operator, span: Span, left_operand: &N, right_operand: &N, mode: N::Mode, overflowed: &mut bool) -> Result<N, ParseError> {
+    let result = match op {
+        Operator::Not => right_operand.not(mode),
+        Operator::Lsl => left_operand.lsl(right_operand, mode),
+        Operator::Lsr => left_operand.lsr(right_operand, mode),
+        Operator::Asl => left_operand.asl(right_operand, mode),
+        Operator::Asr => left_operand.asr(right_operand, mode),
+        Operator::Rol => left_operand.rol(right_operand, mode),
+        Operator::Ror => left_operand.ror(right_operand, mode),
+        Operator::Rcl | Operator::Rcr => unreachable!("rotations through the carry are evaluated with the environment"),
+        Operator::Pow => left_operand.pow(right_operand, mode),
+        Operator::Mul => left_operand.mul(right_operand, mode),
+        Operator::Div => left_operand.div(right_operand, mode),
+        Operator::Mod => left_operand.rem(right_operand, mode),
+        Operator::Add => left_operand.add(right_operand, mode),
+        Operator::Sub => left_operand.sub(right_operand, mode),
+        Operator::And => left_operand.and(right_operand, mode),
+        Operator::Xor => left_operand.xor(right_operand, mode),
+        Operator::Or => left_operand.or(right_operand, mode),
+        Operator::Eq => Ok(N::from_bool(left_operand.compare(right_operand, mode).is_eq(), mode)),
+        Operator::Ne => Ok(N::from_bool(left_operand.compare(right_operand, mode).is_ne(), mode)),
+        Operator::Lt => Ok(N::from_bool(left_operand.compare(right_operand, mode).is_lt(), mode)),
+        Operator::Le => Ok(N::from_bool(left_operand.compare(right_operand, mode).is_le(), mode)),
+        Operator::Gt => Ok(N::from_bool(left_operand.compare(right_operand, mode).is_gt(), mode)),
+        Operator::Ge => Ok(N::from_bool(left_operand.compare(right_operand, mode).is_ge(), mode)),
+        Operator::LogicalAnd => Ok(N::from_bool(!left_operand.is_zero(mode) && !right_operand.is_zero(mode), mode)),
+        Operator::LogicalOr => Ok(N::from_bool(!left_operand.is_zero(mode) || !right_operand.is_zero(mode), mode))
+    };
+
+    check(result, op, span, mode, overflowed)
+}
+
+// Overflows are wrapped or saturated as the policy says, the other failures are errors
+fn check<N: Numeric>(result: Result<N, ArithmeticError<N>>, op: Operator, span: Span, mode: N::Mode, overflowed: &mut bool) -> Result<N, ParseError> {
+    match result {
+        Ok(result) => Ok(result),
+        Err(ArithmeticError::Overflow(overflow)) => handle_overflow(overflow, span, mode, overflowed),
+        Err(error) => Err(to_parse_error(error, op, span, mode))
+    }
+}
+
+fn to_parse_error<N: Numeric>(error: ArithmeticError<N>, op: Operator, span: Span, mode: N::Mode) -> ParseError {
+    match error {
+        ArithmeticError::Overflow(_) => ParseError::Overflow { span },
+        ArithmeticError::DivisionByZero => ParseError::DivisionByZero { span },
+        ArithmeticError::NegativeExponent => ParseError::NegativeExponent { span },
+        ArithmeticError::FractionalExponent => ParseError::FractionalExponent { span },
+        ArithmeticError::InvalidShift => ParseError::InvalidShift { span },
+        ArithmeticError::Unsupported => ParseError::UnsupportedOperator { operator: op.to_string(), format: N::format_name(mode), span }
+    }
+}
+
+fn handle_overflow<N: Numeric>(overflow: Overflow<N>, span: Span, mode: N::Mode, overflowed: &mut bool) -> Result<N, ParseError> {
+    match N::overflow_policy(mode) {
+        OverflowPolicy::Error => Err(ParseError::Overflow { span }),
+        OverflowPolicy::Wrapping => {
+            *overflowed = true;
+            Ok(overflow.wrapped)
+        },
+        OverflowPolicy::Saturating => {
+            *overflowed = true;
+            Ok(N::saturated(overflow.positive, mode))
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::parser::enums::expr::BinaryLink;
+    use crate::parser::parser::num_type::{NumMode, NumType, Width};
+
+    fn literal(value: NumType) -> Expr<NumType> {
+        Expr::Literal { value, span: Span::default() }
+    }
+
+    fn binary(op: Operator, op_span: Span, left: Expr<NumType>, right: Expr<NumType>) -> Expr<NumType> {
+        Expr::Binary { first: Box::new(left), links: vec![BinaryLink { op, op_span, operand: right }], span: Span::default() }
+    }
 
     #[test]
     fn test_semantic_analyzer() {
-        let elements = vec![
-            vec![
-                Element::Number(1),
-                Element::Operator(Op::Add),
-                Element::Number(2),
-                Element::Operator(Op::Mul),
-                Element::Number(3),
-                Element::Operator(Op::Div),
-                Element::Number(2),
-                Element::Operator(Op::Sub),
-                Element::SubExpression(1)
-            ],
-            vec![
-                Element::Number(1),
-                Element::Operator(Op::Add),
-                Element::Number(2)
-            ]
-        ];
-
-        let mut semantic_analyzer = SemanticAnalyzer::new(elements);
-        let result = semantic_analyzer.calculate();
+        // 1 + 2 * 3 / 2 - (1 + 2)
+        let expression = binary(
+            Operator::Sub,
+            Span::default(),
+            binary(
+                Operator::Add,
+                Span::default(),
+                literal(1),
+                binary(Operator::Div, Span::default(), binary(Operator::Mul, Span::default(), literal(2), literal(3)), literal(2))
+            ),
+            Expr::Group {
+                inner: Box::new(binary(Operator::Add, Span::default(), literal(1), literal(2))),
+                span: Span::default()
+            }
+        );
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::default());
+        let result = semantic_analyzer.calculate(&mut Environment::new()).unwrap();
 
         assert_eq!(result, 1);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_semantic_analyzer_unsigned_byte() {
+        let expression = Expr::Unary { op: UnaryOperator::Not, operand: Box::new(literal(0)), span: Span::default() };
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::new(Width::W8, false));
+        let result = semantic_analyzer.calculate(&mut Environment::new()).unwrap();
+
+        assert_eq!(result, 255);
+    }
+
+    #[test]
+    fn test_semantic_analyzer_negation_overflow() {
+        let expression = Expr::Unary { op: UnaryOperator::Neg, operand: Box::new(literal(1)), span: Span::new(0, 2) };
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::new(Width::W8, false));
+
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Err(ParseError::Overflow { span: Span::new(0, 2) }));
+    }
+
+    #[test]
+    fn test_semantic_analyzer_division_by_zero() {
+        let expression = binary(Operator::Div, Span::new(2, 3), literal(1), literal(0));
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::default());
+
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Err(ParseError::DivisionByZero { span: Span::new(2, 3) }));
+    }
+
+    fn addition(left: NumType, right: NumType) -> Expr<NumType> {
+        binary(Operator::Add, Span::new(2, 3), literal(left), literal(right))
+    }
+
+    #[test]
+    fn test_semantic_analyzer_overflow_policies() {
+        let mode = NumMode::new(Width::W8, true);
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(addition(0x7f, 1), mode);
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Err(ParseError::Overflow { span: Span::new(2, 3) }));
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(addition(0x7f, 1), mode.with_overflow_policy(OverflowPolicy::Wrapping));
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Ok(0x80));
+        assert!(semantic_analyzer.overflowed());
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(addition(0x7f, 1), mode.with_overflow_policy(OverflowPolicy::Saturating));
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Ok(0x7f));
+        assert!(semantic_analyzer.overflowed());
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(addition(0x7e, 1), mode.with_overflow_policy(OverflowPolicy::Wrapping));
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Ok(0x7f));
+        assert!(!semantic_analyzer.overflowed());
+    }
+
+    #[test]
+    fn test_semantic_analyzer_invalid_shift() {
+        let expression = binary(Operator::Asl, Span::new(2, 5), literal(1), literal(40));
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::default());
+        assert_eq!(semantic_analyzer.calculate(&mut Environment::new()), Err(ParseError::InvalidShift { span: Span::new(2, 5) }));
+    }
+
+    #[test]
+    fn test_semantic_analyzer_variables() {
+        let mut environment = Environment::new();
+        environment.set("mask", Value::new(0xff00, NumMode::default(), false));
+
+        // reg = 0x1234 and mask
+        let expression = Expr::Assign {
+            name: "reg".to_string(),
+            value: Box::new(binary(Operator::And, Span::default(), literal(0x1234), Expr::Variable { name: "mask".to_string(), span: Span::default() })),
+            span: Span::default()
+        };
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::default());
+        assert_eq!(semantic_analyzer.calculate(&mut environment), Ok(0x1200));
+        assert_eq!(environment.get("reg"), Some(Value::new(0x1200, NumMode::default(), false)));
+    }
+
+    #[test]
+    fn test_semantic_analyzer_undefined_variable() {
+        let expression: Expr<NumType> = Expr::Variable { name: "ans".to_string(), span: Span::new(0, 3) };
+
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::default());
+        assert_eq!(
+            semantic_analyzer.calculate(&mut Environment::new()),
+            Err(ParseError::UndefinedVariable { name: "ans".to_string(), span: Span::new(0, 3) })
+        );
+    }
+
+    #[test]
+    fn test_semantic_analyzer_variable_from_wider_mode() {
+        let mut environment = Environment::new();
+        environment.set("big", Value::new(300, NumMode::default(), false));
+
+        let expression = Expr::Variable { name: "big".to_string(), span: Span::new(0, 3) };
+        let mut semantic_analyzer = SemanticAnalyzer::new(expression, NumMode::new(Width::W8, false));
+
+        assert_eq!(semantic_analyzer.calculate(&mut environment), Err(ParseError::Overflow { span: Span::new(0, 3) }));
+    }
+
+    #[test]
+    fn test_semantic_analyzer_short_circuit() {
+        let division_by_zero = || binary(Operator::Div, Span::new(4, 5), literal(1), literal(0));
+
+        let expression = binary(Operator::LogicalAnd, Span::default(), literal(0), division_by_zero());
+        assert_eq!(SemanticAnalyzer::new(expression, NumMode::default()).calculate(&mut Environment::new()), Ok(0));
+
+        let expression = binary(Operator::LogicalOr, Span::default(), literal(5), division_by_zero());
+        assert_eq!(SemanticAnalyzer::new(expression, NumMode::default()).calculate(&mut Environment::new()), Ok(1));
+
+        let expression = Expr::Conditional {
+            condition: Box::new(literal(0)),
+            if_true: Box::new(division_by_zero()),
+            if_false: Box::new(literal(7)),
+            span: Span::default()
+        };
+        assert_eq!(SemanticAnalyzer::new(expression, NumMode::default()).calculate(&mut Environment::new()), Ok(7));
+
+        let expression = binary(Operator::LogicalAnd, Span::default(), literal(2), division_by_zero());
+        assert_eq!(
+            SemanticAnalyzer::new(expression, NumMode::default()).calculate(&mut Environment::new()),
+            Err(ParseError::DivisionByZero { span: Span::new(4, 5) })
+        );
+    }
+
+    #[test]
+    fn test_semantic_analyzer_signed_comparison() {
+        let expression = binary(Operator::Lt, Span::default(), literal(0xff), literal(1));
+
+        assert_eq!(SemanticAnalyzer::new(expression.clone(), NumMode::new(Width::W8, true)).calculate(&mut Environment::new()), Ok(1));
+        assert_eq!(SemanticAnalyzer::new(expression, NumMode::new(Width::W8, false)).calculate(&mut Environment::new()), Ok(0));
+    }
+
+    #[test]
+    fn test_semantic_analyzer_rotate_through_carry() {
+        let mut environment = Environment::new();
+        let mode = NumMode::new(Width::W8, false);
+
+        let expression = binary(Operator::Rcl, Span::default(), literal(0x81), literal(1));
+        assert_eq!(SemanticAnalyzer::new(expression.clone(), mode).calculate(&mut environment), Ok(0x02));
+        assert!(environment.carry());
+        assert_eq!(SemanticAnalyzer::new(expression, mode).calculate(&mut environment), Ok(0x03));
+
+        let expression = binary(Operator::Rcr, Span::default(), literal(0x02), literal(2));
+        assert_eq!(SemanticAnalyzer::new(expression, mode).calculate(&mut environment), Ok(0x40));
+        assert!(environment.carry());
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use crate::parser::parser::num_type::{NumType, Value};
+use crate::parser::parser::numeric::Numeric;
+
+// Variables kept between evaluations, along with the last result which is read as `ans` or `_`,
+// and the carry flag rotated through by rcl and rcr
+#[derive(Debug, Clone)]
+pub struct Environment<N: Numeric = NumType> {
+    variables: BTreeMap<String, Value<N>>,
+    last_result: Option<Value<N>>,
+    carry: bool
+}
+
+impl<N: Numeric> Default for Environment<N> {
+    fn default() -> Environment<N> {
+        Environment { variables: BTreeMap::new(), last_result: None, carry: false }
+    }
+}
+
+impl Environment {
+    pub const LAST_RESULT_NAMES: [&'static str; 2] = ["ans", "_"];
+}
+
+impl<N: Numeric> Environment<N> {
+    pub fn new() -> Environment<N> {
+        Environment::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value<N>> {
+        if Environment::LAST_RESULT_NAMES.contains(&name) {
+            return self.last_result.clone();
+        }
+
+        self.variables.get(name).cloned()
+    }
+
+    pub fn set(&mut self, name: &str, value: Value<N>) {
+        self.variables.insert(name.to_string(), value);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value<N>> {
+        self.variables.remove(name)
+    }
+
+    pub fn last_result(&self) -> Option<Value<N>> {
+        self.last_result.clone()
+    }
+
+    pub fn set_last_result(&mut self, value: Value<N>) {
+        self.last_result = Some(value);
+    }
+
+    pub fn carry(&self) -> bool {
+        self.carry
+    }
+
+    pub fn set_carry(&mut self, carry: bool) {
+        self.carry = carry;
+    }
+
+    // Sorted by name
+    pub fn variables(&self) -> impl Iterator<Item = (&str, &Value<N>)> {
+        self.variables.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    // Forgets the variables and the last result, and clears the carry
+    pub fn clear(&mut self) {
+        self.variables.clear();
+        self.last_result = None;
+        self.carry = false;
+    }
+}
@@ -1 +1,2 @@
-pub mod operator;
\ No newline at end of file
+pub mod span;
+pub mod environment;
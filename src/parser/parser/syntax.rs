@@ -1,347 +1,474 @@
-use std::collections::VecDeque;
+use std::borrow::Cow;
+use std::cell::Cell;
 
+use super::enums::expr::{BinaryLink, Expr};
+use super::enums::operator::{Operator, UnaryOperator};
 use super::enums::token::Token;
-use super::num_type::NumType;
-
-use super::enums::element::{Element, Operator};
-
-pub struct SyntaxAnalyzer<'a> {
-    tokens: Vec<Token<'a>>
+use super::error::ParseError;
+use super::limits::EvalLimits;
+use super::numeric::{LiteralError, Numeric};
+use super::precedence::{Associativity, PrecedenceTable};
+use super::structs::environment::Environment;
+use super::structs::span::Span;
+
+// Precedence climbing parser building an expression tree from the tokens in a single pass. Every
+// time the parser recurses, into a group, a call, a conditional or the operand of an operator,
+// counts as one level of nesting, checked against the maximum before going deeper so that
+// hostile input cannot overflow the stack. Runs of operators stay one level deep.
+pub struct SyntaxAnalyzer<'a, N: Numeric> {
+    tokens: Vec<(Token<'a>, Span)>,
+    mode: N::Mode,
+    precedence: &'a PrecedenceTable,
+    limits: EvalLimits,
+    depth: Cell<usize>
 }
 
-impl<'a> SyntaxAnalyzer<'a> {
-    pub fn new(tokens: Vec<Token<'a>>) -> SyntaxAnalyzer<'a> {
+impl<'a, N: Numeric> SyntaxAnalyzer<'a, N> {
+    pub fn new(tokens: Vec<(Token<'a>, Span)>, mode: N::Mode, precedence: &'a PrecedenceTable) -> SyntaxAnalyzer<'a, N> {
         SyntaxAnalyzer {
-            tokens
+            tokens,
+            mode,
+            precedence,
+            limits: EvalLimits::default(),
+            depth: Cell::new(0)
         }
     }
 
-    pub fn analyze(&self) -> Result<Vec<Vec<Element<NumType>>>, String>
-    {
-        let mut expressions_tokens = VecDeque::with_capacity(1);
-        expressions_tokens.push_back(&self.tokens[..]);
-
-        let mut interpreted_expressions: Vec<Vec<Element<NumType>>> = Vec::new();
-        let mut subexpression_index = 1usize;
-
-        while let Some(expr_tokens) = expressions_tokens.pop_front() {
-            let mut token_iter = expr_tokens.iter().enumerate();
-            let mut current_token = token_iter.next();
-
-            let mut state = ParserState::Initial(Sign::Positive);
-            let mut current_expression = Vec::new();
-
-            while let Some((token_index, token)) = current_token {
-                match state {
-                    ParserState::Initial(sign) => {
-                        match token {
-                            Token::Number(val) => {
-                                let num = match val.parse::<NumType>() {
-                                    Ok(num) => num,
-                                    Err(_) => {
-                                        return Err(format!("Invalid number: {}", val));
-                                    }
-                                };
-
-                                state = ParserState::Operand;
-                                current_token = token_iter.next();
-
-                                current_expression.push(Element::Number(0 as NumType));
-                                current_expression.push(Element::Operator(match sign {
-                                    Sign::Positive => Operator::Add,
-                                    Sign::Negative => Operator::Sub
-                                }));
-                                current_expression.push(Element::Number(num));
-                            },
-                            Token::Operator("+") => {
-                                current_token = token_iter.next();
-                            },
-                            Token::Operator("-") => {
-                                current_token = token_iter.next();
-                                state = ParserState::Initial(Sign::Negative);
-                            },
-                            Token::ParenthesisOpen => {
-                                state = ParserState::SubExpressionOpen;
-                                current_token = token_iter.next();
-
-                                current_expression.push(Element::Number(0 as NumType));
-                                current_expression.push(Element::Operator(match sign {
-                                    Sign::Positive => Operator::Add,
-                                    Sign::Negative => Operator::Sub
-                                }));
-                                // Push zero
-                                // Push operator (from sign)
-                            },
-                            Token::Operator(val) => {
-                                match *val {
-                                    "not" => {
-                                        state = ParserState::UnaryOperator;
-                                        current_token = token_iter.next();
-
-                                        current_expression.push(Element::Number(0 as NumType));
-                                        current_expression.push(Element::Operator(match sign {
-                                            Sign::Positive => Operator::Add,
-                                            Sign::Negative => Operator::Sub
-                                        }));
-                                        current_expression.push(Element::Operator(Operator::Not));
-                                    },
-                                    _=> {
-                                        return Err(format!("Unexpected operator at position {}", token_index));
-                                    }
-                                }
-                                
-                            },
-                            _ => {
-                                return Err(format!("Unexpected token at position at position {}", token_index));
-                            }
-                        }
-                    },
-                    ParserState::Operand => {
-                        match token {
-                            Token::Number(_) => {
-                                return Err(format!("Cannot have two numbers in a row"));
-                            },
-                            Token::Operator(val) => {
-                                let operator = match Operator::from_str(val) {
-                                    Some(op) => op,
-                                    None => {
-                                        return Err(format!("Unexpected operator: {}", val));
-                                    }
-                                };
-
-                                current_expression.push(Element::Operator(operator));
-
-                                current_token = token_iter.next();
-
-                                state = ParserState::BinaryOperator;
-                            },
-                            Token::ParenthesisOpen => {
-                                current_expression.push(Element::Operator(Operator::Mul));
-
-                                current_token = token_iter.next();
-
-                                state = ParserState::SubExpressionOpen;
-                            },
-                            Token::ParenthesisClose => {
-                                return Err(format!("Unexpected parenthesis close at position {}", token_index));
-                            },
-                        }
-                    },
-                    ParserState::UnaryOperator => {
-                        match token {
-                            Token::Number(val) => {
-                                let num = match val.parse::<NumType>() {
-                                    Ok(num) => num,
-                                    Err(_) => {
-                                        return Err(format!("Invalid number: {}", val));
-                                    }
-                                };
-
-                                current_expression.push(Element::Number(num));
-
-                                current_token = token_iter.next();
-
-                                state = ParserState::Operand;
-                            },
-                            Token::Operator(_) => {
-                                return Err(format!("Cannot have two operators in a row at position {}", token_index));
-                            },
-                            Token::ParenthesisOpen => {
-                                state = ParserState::SubExpressionOpen;
-                                current_token = token_iter.next();
-                            },
-                            Token::ParenthesisClose => {
-                                return Err(format!("Unexpected parenthesis close at position {}", token_index));
-                            },
-                        }
-                    },
-                    ParserState::BinaryOperator => {
-                        match token {
-                            Token::Number(val) => {
-                                let num = match val.parse::<NumType>() {
-                                    Ok(num) => num,
-                                    Err(_) => {
-                                        return Err(format!("Invalid number: {}", val));
-                                    }
-                                };
-
-                                current_expression.push(Element::Number(num));
-
-                                current_token = token_iter.next();
-
-                                state = ParserState::Operand;
-                            },
-                            Token::Operator(_) => {
-                                return Err(format!("Cannot have two operators in a row at position {}", token_index));
-                            },
-                            Token::ParenthesisOpen => {
-                                state = ParserState::SubExpressionOpen;
-                                current_token = token_iter.next();
-                            },
-                            Token::ParenthesisClose => {
-                                return Err(format!("Unexpected parenthesis close at position {}", token_index));
-                            },
-                        };
-                    },
-                    ParserState::SubExpressionOpen => {
-                        let subexpr_beginning = token_index;
-                        let mut parenthesis_count = 1usize;
-                        while let Some((token_index, token)) = current_token {
-                            match token {
-                                Token::ParenthesisOpen => {
-                                    parenthesis_count += 1;
-                                },
-                                Token::ParenthesisClose => {
-                                    if parenthesis_count == 1 {
-                                        expressions_tokens.push_back(&expr_tokens[subexpr_beginning..token_index]);
-                                        current_expression.push(Element::SubExpression(subexpression_index));
-                                        subexpression_index += 1;
-                                        state = ParserState::Operand;
-
-                                        current_token = token_iter.next();
-                                        break;
-                                    } else {
-                                        parenthesis_count -= 1;
-                                    }
-                                },
-                                _ => {}
-                            }
-
-                            current_token = token_iter.next();
-                        }
-
-                        if parenthesis_count != 1 {
-                            return Err(format!("Unmatched parenthesis")); // TODO, add position
-                        }
-                    }
-                }
+    pub fn with_limits(self, limits: EvalLimits) -> SyntaxAnalyzer<'a, N> {
+        SyntaxAnalyzer { limits, ..self }
+    }
+
+    // Goes one level deeper, the span being the token that opens the level. Every successful
+    // call is paired with a call to leave once the level is parsed, errors end the analysis.
+    fn enter(&self, span: Span) -> Result<(), ParseError> {
+        if self.depth.get() >= self.limits.max_depth() {
+            return Err(ParseError::NestingTooDeep { limit: self.limits.max_depth(), span });
+        }
+
+        self.depth.set(self.depth.get() + 1);
+        Ok(())
+    }
+
+    fn leave(&self) {
+        self.depth.set(self.depth.get() - 1);
+    }
+
+    pub fn analyze(&self) -> Result<Expr<N>, ParseError> {
+        let mut position = 0;
+        let expression = self.parse_statement(&mut position)?;
+
+        match self.tokens.get(position) {
+            None => Ok(expression),
+            Some((Token::ParenthesisClose, span)) => Err(ParseError::UnmatchedParen { span: *span }),
+            Some((token, span)) => Err(unexpected_token(token, *span))
+        }
+    }
+
+    // An assignment `name = value`, where the value can be another assignment, or an expression
+    fn parse_statement(&self, position: &mut usize) -> Result<Expr<N>, ParseError> {
+        let (name, name_span) = match self.tokens.get(*position..*position + 2) {
+            Some([(Token::Identifier(name), name_span), (Token::Assign, _)]) => (*name, *name_span),
+            _ => return self.parse_conditional(position)
+        };
+
+        if Environment::LAST_RESULT_NAMES.contains(&name) {
+            return Err(ParseError::ReadOnlyVariable { name: name.to_string(), span: name_span });
+        }
+
+        *position += 2;
+        self.enter(name_span)?;
+        let value = self.parse_statement(position)?;
+        self.leave();
+        let span = Span::new(name_span.start, value.span().end);
+
+        Ok(Expr::Assign { name: name.to_string(), value: Box::new(value), span })
+    }
+
+    // condition ? if_true : if_false, binding looser than every binary operator and nesting
+    // to the right, as in C
+    fn parse_conditional(&self, position: &mut usize) -> Result<Expr<N>, ParseError> {
+        let condition = self.parse_expression(position, 0)?;
+
+        let question_span = match self.tokens.get(*position) {
+            Some((Token::Question, span)) => *span,
+            _ => return Ok(condition)
+        };
+        *position += 1;
+
+        self.enter(question_span)?;
+        let if_true = self.parse_conditional(position)?;
+        match self.tokens.get(*position) {
+            Some((Token::Colon, _)) => *position += 1,
+            _ => return Err(ParseError::MissingColon { span: question_span })
+        }
+
+        let if_false = self.parse_conditional(position)?;
+        self.leave();
+        let span = Span::new(condition.span().start, if_false.span().end);
+
+        Ok(Expr::Conditional {
+            condition: Box::new(condition),
+            if_true: Box::new(if_true),
+            if_false: Box::new(if_false),
+            span
+        })
+    }
+
+    // Parses operands joined by binary operators binding tighter than the given precedence, as one
+    // node applying them from left to right
+    fn parse_expression(&self, position: &mut usize, min_precedence: u8) -> Result<Expr<N>, ParseError> {
+        let first = self.parse_unary(position)?;
+        let mut links = Vec::new();
+
+        while let Some((token, span)) = self.tokens.get(*position) {
+            let operator = match token {
+                Token::Operator(val) => match Operator::from_str(val) {
+                    Some(operator) => operator,
+                    None => return Err(unexpected_token(token, *span))
+                },
+                // Implicit multiplication, as in 2(3 + 1). The parenthesis is left for the operand.
+                Token::ParenthesisOpen => Operator::Mul,
+                Token::ParenthesisClose | Token::Comma | Token::Question | Token::Colon => break,
+                // A word where an operator is expected is most likely a misspelled keyword
+                Token::Identifier(word) => return Err(ParseError::UnknownWord { found: word.to_string(), span: *span }),
+                Token::Number(_, _) | Token::Assign => return Err(unexpected_token(token, *span))
+            };
+
+            // Operators missing from the table, such as not, are prefix only
+            let (precedence, associativity) = match self.precedence.get(operator) {
+                Some(entry) => entry,
+                None => return Err(unexpected_token(token, *span))
+            };
+
+            if precedence <= min_precedence {
+                break;
+            }
+
+            let operator_span = *span;
+            if *token != Token::ParenthesisOpen {
+                *position += 1;
+            }
+
+            // A right associative operator takes the operators of its own level on its right
+            let right_precedence = match associativity {
+                Associativity::Left => precedence,
+                Associativity::Right => precedence - 1
+            };
+            self.enter(operator_span)?;
+            let operand = self.parse_expression(position, right_precedence)?;
+            self.leave();
+
+            links.push(BinaryLink { op: operator, op_span: operator_span, operand });
+        }
+
+        match links.last() {
+            None => Ok(first),
+            Some(last) => {
+                let span = Span::new(first.span().start, last.operand.span().end);
+                Ok(Expr::Binary { first: Box::new(first), links, span })
             }
+        }
+    }
+
+    fn parse_unary(&self, position: &mut usize) -> Result<Expr<N>, ParseError> {
+        let op = match self.tokens.get(*position) {
+            Some((Token::Operator("-"), _)) => UnaryOperator::Neg,
+            Some((Token::Operator(op), _)) if Operator::from_str(op) == Some(Operator::Not) => UnaryOperator::Not,
+            Some((Token::Operator("!"), _)) => UnaryOperator::LogicalNot,
+            Some((Token::Operator("+"), span)) => {
+                *position += 1;
+                self.enter(*span)?;
+                let operand = self.parse_unary(position)?;
+                self.leave();
+                return Ok(operand);
+            },
+            _ => return self.parse_primary(position)
+        };
+
+        let op_span = self.tokens[*position].1;
+        *position += 1;
+
+        if let (UnaryOperator::Neg, Some(literal)) = (op, self.negative_literal(op_span, *position)) {
+            *position += 1;
+            return Ok(literal);
+        }
+
+        // Takes the binary operators binding tighter than the prefix ones, as the power
+        self.enter(op_span)?;
+        let operand = self.parse_expression(position, self.precedence.prefix())?;
+        self.leave();
+        let span = Span::new(op_span.start, operand.span().end);
 
-            if state != ParserState::Operand {
-                return Err(format!("Unexpected end of expression"));
+        Ok(Expr::Unary { op, operand: Box::new(operand), span })
+    }
+
+    // A minus before a decimal literal is part of it, so the most negative number can be written,
+    // unless an operator binding tighter than the minus takes the literal, as in -2 ** 2. None when
+    // the negated literal does not read, leaving the minus to apply the overflow policy.
+    fn negative_literal(&self, op_span: Span, position: usize) -> Option<Expr<N>> {
+        let (digits, span) = match self.tokens.get(position) {
+            Some((Token::Number(digits, 10), span)) => (*digits, *span),
+            _ => return None
+        };
+
+        let operator = match self.tokens.get(position + 1) {
+            Some((Token::Operator(val), _)) => Operator::from_str(val),
+            Some((Token::ParenthesisOpen, _)) => Some(Operator::Mul),
+            _ => None
+        };
+        if operator.and_then(|operator| self.precedence.get(operator)).is_some_and(|(precedence, _)| precedence > self.precedence.prefix()) {
+            return None;
+        }
+
+        let span = Span::new(op_span.start, span.end);
+        self.literal(&format!("-{}", digits), 10, span).ok()
+    }
+
+    fn literal(&self, digits: &str, radix: u32, span: Span) -> Result<Expr<N>, ParseError> {
+        let value = parse_number::<N>(digits, radix, span, self.mode)?;
+        if value.magnitude_bits().is_some_and(|bits| bits > self.limits.max_big_int_bits()) {
+            return Err(ParseError::NumberTooLarge { limit: self.limits.max_big_int_bits(), span });
+        }
+
+        Ok(Expr::Literal { value, span })
+    }
+
+    fn parse_primary(&self, position: &mut usize) -> Result<Expr<N>, ParseError> {
+        let (token, span) = match self.tokens.get(*position) {
+            Some((token, span)) => (token, *span),
+            None => {
+                let end_position = self.tokens.last().map_or(0, |(_, span)| span.end);
+                return Err(ParseError::UnexpectedEnd { span: Span::new(end_position, end_position) });
             }
+        };
+
+        match token {
+            Token::Number(val, radix) => {
+                *position += 1;
+
+                self.literal(val, *radix, span)
+            },
+            Token::Identifier(name) => {
+                *position += 1;
+
+                match self.tokens.get(*position) {
+                    Some((Token::ParenthesisOpen, _)) => self.parse_call(name, span, position),
+                    _ => Ok(Expr::Variable { name: name.to_string(), span })
+                }
+            },
+            Token::ParenthesisOpen => {
+                *position += 1;
+
+                self.enter(span)?;
+                let inner = self.parse_conditional(position)?;
+                self.leave();
+                match self.tokens.get(*position) {
+                    Some((Token::ParenthesisClose, close_span)) => {
+                        *position += 1;
+                        Ok(Expr::Group { inner: Box::new(inner), span: Span::new(span.start, close_span.end) })
+                    },
+                    None => Err(ParseError::UnmatchedParen { span }),
+                    Some((token, span)) => Err(unexpected_token(token, *span))
+                }
+            },
+            // The enclosing group ends where an operand was expected
+            Token::ParenthesisClose => Err(ParseError::UnexpectedEnd { span: Span::new(span.start, span.start) }),
+            Token::Operator(_) | Token::Assign | Token::Comma | Token::Question | Token::Colon => Err(unexpected_token(token, span))
+        }
+    }
+
+    // A name directly followed by a parenthesis is always a call, `x * (1)` multiplies a variable
+    fn parse_call(&self, name: &str, name_span: Span, position: &mut usize) -> Result<Expr<N>, ParseError> {
+        let open_span = self.tokens[*position].1;
+        *position += 1;
+
+        let mut args = Vec::new();
+        if let Some((Token::ParenthesisClose, close_span)) = self.tokens.get(*position) {
+            *position += 1;
+            return Ok(Expr::Call { name: name.to_string(), name_span, args, span: Span::new(name_span.start, close_span.end) });
+        }
 
-            interpreted_expressions.push(current_expression);
+        self.enter(open_span)?;
+        loop {
+            args.push(self.parse_conditional(position)?);
+
+            match self.tokens.get(*position) {
+                Some((Token::Comma, _)) => *position += 1,
+                Some((Token::ParenthesisClose, close_span)) => {
+                    *position += 1;
+                    self.leave();
+                    return Ok(Expr::Call { name: name.to_string(), name_span, args, span: Span::new(name_span.start, close_span.end) });
+                },
+                None => return Err(ParseError::UnmatchedParen { span: open_span }),
+                Some((token, span)) => return Err(unexpected_token(token, *span))
+            }
         }
-        
-        return Ok(interpreted_expressions);
     }
 }
 
-// Analyze tokens
-#[derive(Clone, Copy, PartialEq)]
-enum Sign {
-    Positive,
-    Negative
+fn parse_number<N: Numeric>(digits: &str, radix: u32, span: Span, mode: N::Mode) -> Result<N, ParseError> {
+    let digits = match digits.contains('_') {
+        true => Cow::Owned(digits.replace('_', "")),
+        false => Cow::Borrowed(digits)
+    };
+
+    match N::parse(&digits, radix, mode) {
+        Ok(num) => Ok(num),
+        Err(LiteralError::Overflow) => Err(ParseError::Overflow { span }),
+        Err(LiteralError::Invalid) => Err(ParseError::InvalidNumber { span })
+    }
 }
 
-#[derive(PartialEq)]
-enum ParserState {
-    Initial(Sign),
-    Operand,
-    UnaryOperator,
-    BinaryOperator,
-    SubExpressionOpen
+fn unexpected_token(token: &Token, span: Span) -> ParseError {
+    let found = match token {
+        Token::Number(digits, _) => digits.to_string(),
+        Token::Operator(op) | Token::Identifier(op) => op.to_string(),
+        Token::Assign => "=".to_string(),
+        Token::Comma => ",".to_string(),
+        Token::Question => "?".to_string(),
+        Token::Colon => ":".to_string(),
+        Token::ParenthesisOpen => "(".to_string(),
+        Token::ParenthesisClose => ")".to_string()
+    };
+
+    ParseError::UnexpectedToken { found, span }
 }
 
 //tests
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::parser::parser::num_type::{NumMode, NumType, Width};
+
+    fn spanned(tokens: Vec<Token>) -> Vec<(Token, Span)> {
+        tokens.into_iter().enumerate().map(|(index, token)| (token, Span::new(index, index + 1))).collect()
+    }
+
+    fn analyze(tokens: Vec<Token>) -> Result<Expr<NumType>, ParseError> {
+        SyntaxAnalyzer::new(spanned(tokens), NumMode::default(), &PrecedenceTable::default()).analyze()
+    }
+
+    // Fully parenthesized prefix form, to check the shape of the tree
+    fn shape(expression: &Expr<NumType>) -> String {
+        match expression {
+            Expr::Literal { value, .. } => NumMode::default().sign_extend(*value).to_string(),
+            Expr::Unary { op: UnaryOperator::Neg, operand, .. } => format!("(neg {})", shape(operand)),
+            Expr::Unary { op: UnaryOperator::Not, operand, .. } => format!("(not {})", shape(operand)),
+            Expr::Unary { op: UnaryOperator::LogicalNot, operand, .. } => format!("(! {})", shape(operand)),
+            Expr::Binary { first, links, .. } => links.iter()
+                .fold(shape(first), |left, link| format!("({} {} {})", link.op, left, shape(&link.operand))),
+            Expr::Group { inner, .. } => format!("(group {})", shape(inner)),
+            Expr::Conditional { condition, if_true, if_false, .. } => format!("(? {} {} {})", shape(condition), shape(if_true), shape(if_false)),
+            Expr::Variable { name, .. } => name.clone(),
+            Expr::Call { name, args, .. } => format!("({} {})", name, args.iter().map(shape).collect::<Vec<String>>().join(" ")),
+            Expr::Assign { name, value, .. } => format!("(= {} {})", name, shape(value))
+        }
+    }
 
     #[test]
     fn test_analyze_tokens_single_number() {
-        let tokens = vec![Token::Number("42")];
-        
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        let result = analyzer.analyze();
+        let tokens = vec![Token::Number("42", 10)];
 
-        assert!(result.is_ok());
+        assert_eq!(analyze(tokens), Ok(Expr::Literal { value: 42, span: Span::new(0, 1) }));
+    }
 
-        let expressions = result.unwrap();
+    #[test]
+    fn test_analyze_tokens_simple_addition() {
+        let tokens = vec![
+            Token::Number("1", 10),
+            Token::Operator("+"),
+            Token::Number("2", 10)
+        ];
 
-        assert_eq!(expressions.len(), 1);
-        assert_eq!(expressions[0], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(42)
-        ]);
+        assert_eq!(analyze(tokens), Ok(Expr::Binary {
+            first: Box::new(Expr::Literal { value: 1, span: Span::new(0, 1) }),
+            links: vec![BinaryLink { op: Operator::Add, op_span: Span::new(1, 2), operand: Expr::Literal { value: 2, span: Span::new(2, 3) } }],
+            span: Span::new(0, 3)
+        }));
     }
 
     #[test]
-    fn test_analyze_tokens_simple_addition() {
+    fn test_analyze_tokens_left_associativity() {
+        let tokens = vec![
+            Token::Number("5", 10),
+            Token::Operator("-"),
+            Token::Number("3", 10),
+            Token::Operator("-"),
+            Token::Number("1", 10)
+        ];
+
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(- (- 5 3) 1)");
+    }
+
+    #[test]
+    fn test_analyze_tokens_precedence() {
         let tokens = vec![
-            Token::Number("1"),
+            Token::Number("1", 10),
+            Token::Operator("or"),
+            Token::Number("2", 10),
             Token::Operator("+"),
-            Token::Number("2")
+            Token::Number("3", 10),
+            Token::Operator("*"),
+            Token::Number("4", 10),
+            Token::Operator("lsl"),
+            Token::Number("5", 10),
+            Token::Operator("and"),
+            Token::Number("6", 10)
         ];
 
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        
-        let result = analyzer.analyze();
-        assert!(result.is_ok());
-        let expressions = result.unwrap();
-        //println!("{:?}", expressions);
-        assert_eq!(expressions.len(), 1);
-        assert_eq!(expressions[0], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(1),
-            Element::Operator(Operator::Add),
-            Element::Number(2)
-        ]);
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(or 1 (and (+ 2 (* 3 (lsl 4 5))) 6))");
     }
 
     #[test]
-    fn test_analyze_tokens_simple_subtraction() {
+    fn test_analyze_tokens_unary_operators() {
         let tokens = vec![
-            Token::Number("5"),
             Token::Operator("-"),
-            Token::Number("3")
+            Token::Number("2", 10),
+            Token::Operator("*"),
+            Token::Operator("not"),
+            Token::Operator("+"),
+            Token::Number("3", 10)
         ];
-        
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        
-        let result = analyzer.analyze();
-        assert!(result.is_ok());
-        let expressions = result.unwrap();
-        //println!("{:?}", expressions);
-        assert_eq!(expressions.len(), 1);
-        assert_eq!(expressions[0], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(5),
-            Element::Operator(Operator::Sub),
-            Element::Number(3)
-        ]);
+
+        let expression = analyze(tokens).unwrap();
+        assert_eq!(shape(&expression), "(* -2 (not 3))");
+        assert_eq!(expression.span(), Span::new(0, 6));
     }
 
     #[test]
     fn test_analyze_tokens_unexpected_token() {
         let tokens = vec![
-            Token::Number("5"),
+            Token::Number("5", 10),
             Token::Operator("*")
         ];
 
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        
-        let result = analyzer.analyze();
-        assert!(result.is_err());
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedEnd { span: Span::new(2, 2) }));
+
+        let tokens = vec![
+            Token::Number("5", 10),
+            Token::Number("3", 10)
+        ];
+
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedToken { found: "3".to_string(), span: Span::new(1, 2) }));
     }
 
     #[test]
     fn test_analyze_tokens_unmatched_parenthesis() {
         let tokens = vec![
             Token::ParenthesisOpen,
-            Token::Number("5"),
+            Token::Number("5", 10),
             Token::Operator("+"),
-            Token::Number("3")
+            Token::Number("3", 10)
+        ];
+
+        assert_eq!(analyze(tokens), Err(ParseError::UnmatchedParen { span: Span::new(0, 1) }));
+
+        let tokens = vec![
+            Token::Number("5", 10),
+            Token::ParenthesisClose
         ];
 
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        
-        let result = analyzer.analyze();
-        assert!(result.is_err());
+        assert_eq!(analyze(tokens), Err(ParseError::UnmatchedParen { span: Span::new(1, 2) }));
     }
 
     #[test]
@@ -349,123 +476,334 @@ mod tests {
         let tokens = vec![
             Token::ParenthesisOpen,
             Token::ParenthesisOpen,
-            Token::Number("5"),
+            Token::Number("5", 10),
             Token::Operator("+"),
-            Token::Number("3"),
+            Token::Number("3", 10),
             Token::ParenthesisClose
         ];
 
-        let analyzer = SyntaxAnalyzer::new(tokens);
-
-        let result = analyzer.analyze();
-        assert!(result.is_err());
+        assert_eq!(analyze(tokens), Err(ParseError::UnmatchedParen { span: Span::new(0, 1) }));
     }
 
     #[test]
     fn test_analyze_tokens_nested_parenthesis() {
         let tokens = vec![
             Token::ParenthesisOpen,
-            Token::Number("5"),
+            Token::Number("5", 10),
             Token::Operator("+"),
             Token::ParenthesisOpen,
-            Token::Number("3"),
+            Token::Number("3", 10),
             Token::Operator("*"),
-            Token::Number("2"),
+            Token::Number("2", 10),
             Token::ParenthesisClose,
             Token::ParenthesisClose
         ];
 
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        
-        let result = analyzer.analyze();
-        assert!(result.is_ok());
-        let expressions = result.unwrap();
-        //println!("{:?}", expressions);
-        assert_eq!(expressions.len(), 3);
-        assert_eq!(expressions[0], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::SubExpression(1)
-        ]);
-
-        assert_eq!(expressions[1], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(5),
-            Element::Operator(Operator::Add),
-            Element::SubExpression(2)
-        ]);
-
-        assert_eq!(expressions[2], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(3),
-            Element::Operator(Operator::Mul),
-            Element::Number(2)
-        ]);
+        let expression = analyze(tokens).unwrap();
+        assert_eq!(shape(&expression), "(group (+ 5 (group (* 3 2))))");
+        assert_eq!(expression.span(), Span::new(0, 9));
     }
 
     #[test]
     fn test_analyze_tokens_multiple_samelevel_parenthesis() {
         let tokens = vec![
-            Token::Number("2"),
+            Token::Number("2", 10),
             Token::Operator("-"),
             Token::ParenthesisOpen,
-            Token::Number("5"),
+            Token::Number("5", 10),
             Token::Operator("+"),
             Token::ParenthesisOpen,
-            Token::Number("3"),
+            Token::Number("3", 10),
             Token::Operator("*"),
-            Token::Number("2"),
+            Token::Number("2", 10),
             Token::ParenthesisClose,
             Token::ParenthesisClose,
             Token::Operator("/"),
             Token::ParenthesisOpen,
-            Token::Number("4"),
+            Token::Number("4", 10),
             Token::Operator("+"),
-            Token::Number("2"),
+            Token::Number("2", 10),
             Token::ParenthesisClose
         ];
 
-        let analyzer = SyntaxAnalyzer::new(tokens);
-        
-        let result = analyzer.analyze();
-        assert!(result.is_ok());
-        let expressions = result.unwrap();
-        //println!("{:?}", expressions);
-        assert_eq!(expressions.len(), 4);
-        assert_eq!(expressions[0], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(2),
-            Element::Operator(Operator::Sub),
-            Element::SubExpression(1),
-            Element::Operator(Operator::Div),
-            Element::SubExpression(2)
-        ]);
-
-        assert_eq!(expressions[1], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(5),
-            Element::Operator(Operator::Add),
-            Element::SubExpression(3)
-        ]);
-
-        assert_eq!(expressions[2], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(4),
-            Element::Operator(Operator::Add),
-            Element::Number(2)
-        ]);
-
-        assert_eq!(expressions[3], vec![
-            Element::Number(0),
-            Element::Operator(Operator::Add),
-            Element::Number(3),
-            Element::Operator(Operator::Mul),
-            Element::Number(2)
-        ]);
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(- 2 (/ (group (+ 5 (group (* 3 2)))) (group (+ 4 2))))");
+    }
+
+    #[test]
+    fn test_analyze_tokens_implicit_multiplication() {
+        let tokens = vec![
+            Token::Number("2", 10),
+            Token::ParenthesisOpen,
+            Token::Number("3", 10),
+            Token::ParenthesisClose,
+            Token::Operator("+"),
+            Token::Number("1", 10)
+        ];
+
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(+ (* 2 (group 3)) 1)");
+    }
+
+    #[test]
+    fn test_analyze_tokens_unexpected_end_position() {
+        let tokens = vec![
+            Token::Number("5", 10),
+            Token::Operator("*"),
+            Token::ParenthesisOpen,
+            Token::Number("3", 10),
+            Token::Operator("-"),
+            Token::ParenthesisClose
+        ];
+
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedEnd { span: Span::new(5, 5) }));
+    }
+
+    #[test]
+    fn test_analyze_tokens_number_errors() {
+        let tokens = vec![Token::Number("100", 16)];
+        let precedence = PrecedenceTable::default();
+        let analyzer = SyntaxAnalyzer::<NumType>::new(spanned(tokens), NumMode::new(Width::W8, false), &precedence);
+        assert_eq!(analyzer.analyze(), Err(ParseError::Overflow { span: Span::new(0, 1) }));
+
+        let tokens = vec![Token::Number("_", 10)];
+        assert_eq!(analyze(tokens), Err(ParseError::InvalidNumber { span: Span::new(0, 1) }));
+    }
+
+    #[test]
+    fn test_analyze_tokens_assignment() {
+        let tokens = vec![
+            Token::Identifier("a"),
+            Token::Assign,
+            Token::Identifier("b"),
+            Token::Assign,
+            Token::Identifier("reg"),
+            Token::Operator("and"),
+            Token::Number("ff", 16)
+        ];
+
+        let expression = analyze(tokens).unwrap();
+        assert_eq!(shape(&expression), "(= a (= b (and reg 255)))");
+        assert_eq!(expression.span(), Span::new(0, 7));
+    }
+
+    #[test]
+    fn test_analyze_tokens_assignment_errors() {
+        let tokens = vec![
+            Token::Number("1", 10),
+            Token::Operator("+"),
+            Token::Identifier("a"),
+            Token::Assign,
+            Token::Number("2", 10)
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedToken { found: "=".to_string(), span: Span::new(3, 4) }));
+
+        let tokens = vec![
+            Token::Identifier("ans"),
+            Token::Assign,
+            Token::Number("2", 10)
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::ReadOnlyVariable { name: "ans".to_string(), span: Span::new(0, 1) }));
+    }
+
+    #[test]
+    fn test_analyze_tokens_word_instead_of_operator() {
+        let tokens = vec![
+            Token::Number("1", 10),
+            Token::Identifier("lsx"),
+            Token::Number("2", 10)
+        ];
+
+        assert_eq!(analyze(tokens), Err(ParseError::UnknownWord { found: "lsx".to_string(), span: Span::new(1, 2) }));
+    }
+
+    #[test]
+    fn test_analyze_tokens_function_call() {
+        let tokens = vec![
+            Token::Identifier("rotl"),
+            Token::ParenthesisOpen,
+            Token::Identifier("x"),
+            Token::Operator("+"),
+            Token::Number("1", 10),
+            Token::Comma,
+            Token::Identifier("popcount"),
+            Token::ParenthesisOpen,
+            Token::Number("3", 10),
+            Token::ParenthesisClose,
+            Token::ParenthesisClose,
+            Token::Operator("*"),
+            Token::Identifier("x"),
+            Token::ParenthesisOpen,
+            Token::ParenthesisClose
+        ];
+
+        let expression = analyze(tokens).unwrap();
+        assert_eq!(shape(&expression), "(* (rotl (+ x 1) (popcount 3)) (x ))");
+        assert_eq!(expression.to_string(), "rotl(x + 1, popcount(3)) * x()");
+    }
+
+    #[test]
+    fn test_analyze_tokens_function_call_errors() {
+        let tokens = vec![
+            Token::Identifier("max"),
+            Token::ParenthesisOpen,
+            Token::Number("1", 10),
+            Token::Comma
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedEnd { span: Span::new(4, 4) }));
+
+        let tokens = vec![
+            Token::Identifier("max"),
+            Token::ParenthesisOpen,
+            Token::Number("1", 10)
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::UnmatchedParen { span: Span::new(1, 2) }));
+
+        let tokens = vec![
+            Token::Number("1", 10),
+            Token::Comma,
+            Token::Number("2", 10)
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedToken { found: ",".to_string(), span: Span::new(1, 2) }));
+    }
+
+    #[test]
+    fn test_analyze_tokens_precedence_tables() {
+        let tokens = || vec![
+            Token::Number("1", 10),
+            Token::Operator("<<"),
+            Token::Number("2", 10),
+            Token::Operator("+"),
+            Token::Number("3", 10),
+            Token::Operator("-"),
+            Token::Number("4", 10)
+        ];
+        let analyze_with = |precedence: &PrecedenceTable| {
+            shape(&SyntaxAnalyzer::new(spanned(tokens()), NumMode::default(), precedence).analyze().unwrap())
+        };
+
+        assert_eq!(analyze_with(&PrecedenceTable::default()), "(- (+ (lsl 1 2) 3) 4)");
+        assert_eq!(analyze_with(&PrecedenceTable::c()), "(lsl 1 (- (+ 2 3) 4))");
+
+        let right_associative = PrecedenceTable::c()
+            .with(Operator::Add, 9, Associativity::Right)
+            .with(Operator::Sub, 9, Associativity::Right);
+        assert_eq!(analyze_with(&right_associative), "(lsl 1 (+ 2 (- 3 4)))");
+    }
+
+    #[test]
+    fn test_analyze_tokens_power() {
+        let tokens = vec![
+            Token::Operator("-"),
+            Token::Number("2", 10),
+            Token::Operator("**"),
+            Token::Number("3", 10),
+            Token::Operator("**"),
+            Token::Operator("-"),
+            Token::Number("2", 10),
+            Token::Operator("*"),
+            Token::Number("5", 10)
+        ];
+
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(* (neg (** 2 (** 3 -2))) 5)");
+    }
+
+    #[test]
+    fn test_analyze_tokens_negative_literals() {
+        let tokens = vec![Token::Operator("-"), Token::Number("2147483648", 10)];
+        assert_eq!(analyze(tokens), Ok(Expr::Literal { value: 0x8000_0000, span: Span::new(0, 2) }));
+
+        let tokens = vec![Token::Operator("-"), Token::Number("2147483649", 10)];
+        assert_eq!(analyze(tokens), Err(ParseError::Overflow { span: Span::new(1, 2) }));
+
+        let tokens = vec![Token::Operator("-"), Token::Number("80000000", 16)];
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(neg -2147483648)");
+
+        let tokens = vec![Token::Operator("-"), Token::Number("2", 10), Token::Operator("**"), Token::Number("2", 10)];
+        assert_eq!(shape(&analyze(tokens).unwrap()), "(neg (** 2 2))");
+    }
+
+    #[test]
+    fn test_analyze_tokens_comparisons() {
+        let tokens = || vec![
+            Token::Operator("!"),
+            Token::Identifier("x"),
+            Token::Operator("||"),
+            Token::Identifier("x"),
+            Token::Operator("&"),
+            Token::Number("15", 10),
+            Token::Operator("=="),
+            Token::Number("3", 10),
+            Token::Operator("&&"),
+            Token::Identifier("y"),
+            Token::Operator("<"),
+            Token::Number("2", 10)
+        ];
+
+        assert_eq!(shape(&analyze(tokens()).unwrap()), "(|| (! x) (&& (== (and x 15) 3) (< y 2)))");
+
+        let precedence = PrecedenceTable::c();
+        let expression = SyntaxAnalyzer::new(spanned(tokens()), NumMode::default(), &precedence).analyze().unwrap();
+        assert_eq!(shape(&expression), "(|| (! x) (&& (and x (== 15 3)) (< y 2)))");
+    }
+
+    #[test]
+    fn test_analyze_tokens_conditional() {
+        let tokens = vec![
+            Token::Identifier("a"),
+            Token::Assign,
+            Token::Identifier("x"),
+            Token::Operator(">"),
+            Token::Number("1", 10),
+            Token::Question,
+            Token::Number("2", 10),
+            Token::Colon,
+            Token::Identifier("y"),
+            Token::Question,
+            Token::Number("3", 10),
+            Token::Colon,
+            Token::Number("4", 10)
+        ];
+
+        let expression = analyze(tokens).unwrap();
+        assert_eq!(shape(&expression), "(= a (? (> x 1) 2 (? y 3 4)))");
+        assert_eq!(expression.to_string(), "a = x > 1 ? 2 : y ? 3 : 4");
+    }
+
+    #[test]
+    fn test_analyze_tokens_conditional_errors() {
+        let tokens = vec![
+            Token::Number("1", 10),
+            Token::Question,
+            Token::Number("2", 10)
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::MissingColon { span: Span::new(1, 2) }));
+
+        let tokens = vec![
+            Token::Number("1", 10),
+            Token::Colon,
+            Token::Number("2", 10)
+        ];
+        assert_eq!(analyze(tokens), Err(ParseError::UnexpectedToken { found: ":".to_string(), span: Span::new(1, 2) }));
+    }
+
+    #[test]
+    fn test_analyze_tokens_nesting_depth() {
+        let precedence = PrecedenceTable::default();
+        let nested = |depth: usize| {
+            let mut tokens: Vec<Token> = (0..depth).map(|_| Token::ParenthesisOpen).collect();
+            tokens.push(Token::Number("1", 10));
+            tokens.extend((0..depth).map(|_| Token::ParenthesisClose));
+            SyntaxAnalyzer::<NumType>::new(spanned(tokens), NumMode::default(), &precedence).with_limits(EvalLimits::default().with_max_depth(3)).analyze()
+        };
+        assert!(nested(3).is_ok());
+        assert_eq!(nested(4), Err(ParseError::NestingTooDeep { limit: 3, span: Span::new(3, 4) }));
+
+        let tokens = vec![
+            Token::Operator("-"),
+            Token::Operator("~"),
+            Token::Operator("-"),
+            Token::Identifier("x")
+        ];
+        let analyzer = SyntaxAnalyzer::<NumType>::new(spanned(tokens), NumMode::default(), &precedence);
+        assert_eq!(analyzer.with_limits(EvalLimits::default().with_max_depth(2)).analyze(), Err(ParseError::NestingTooDeep { limit: 2, span: Span::new(2, 3) }));
     }
 }
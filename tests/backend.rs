@@ -0,0 +1,178 @@
+use std::cmp::Ordering;
+use std::fmt;
+
+use programmer_calc_parser::{ArithmeticError, BigInt, Environment, LiteralError, Numeric, Outcome, Overflow, OverflowPolicy, ParseError, Parser, Span, Value};
+
+// Integers modulo a prime, a backend written outside the crate. Division multiplies by the inverse.
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Residue(u64);
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Prime(u64);
+
+impl fmt::Display for Residue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl fmt::Display for Prime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "mod {}", self.0)
+    }
+}
+
+fn residue(value: u128, prime: Prime) -> Residue {
+    Residue((value % prime.0 as u128) as u64)
+}
+
+impl Numeric for Residue {
+    type Mode = Prime;
+
+    fn parse(digits: &str, radix: u32, prime: Prime) -> Result<Residue, LiteralError> {
+        u128::from_str_radix(digits, radix).map(|value| residue(value, prime)).map_err(|_| LiteralError::Invalid)
+    }
+
+    fn convert(value: &Value<Residue>, prime: Prime) -> Result<Residue, Overflow<Residue>> {
+        Ok(residue(value.raw().0 as u128, prime))
+    }
+
+    fn from_bool(value: bool, _: Prime) -> Residue {
+        Residue(value as u64)
+    }
+
+    fn is_zero(&self, _: Prime) -> bool {
+        self.0 == 0
+    }
+
+    fn compare(&self, other: &Residue, _: Prime) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+
+    fn neg(&self, prime: Prime) -> Outcome<Residue> {
+        Ok(residue((prime.0 - self.0) as u128, prime))
+    }
+
+    fn not(&self, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn add(&self, other: &Residue, prime: Prime) -> Outcome<Residue> {
+        Ok(residue(self.0 as u128 + other.0 as u128, prime))
+    }
+
+    fn sub(&self, other: &Residue, prime: Prime) -> Outcome<Residue> {
+        Ok(residue(self.0 as u128 + (prime.0 - other.0) as u128, prime))
+    }
+
+    fn mul(&self, other: &Residue, prime: Prime) -> Outcome<Residue> {
+        Ok(residue(self.0 as u128 * other.0 as u128, prime))
+    }
+
+    // Fermat's little theorem gives the inverse as the power p - 2
+    fn div(&self, other: &Residue, prime: Prime) -> Outcome<Residue> {
+        match other.0 {
+            0 => Err(ArithmeticError::DivisionByZero),
+            _ => self.mul(&other.pow(&Residue(prime.0 - 2), prime)?, prime)
+        }
+    }
+
+    fn rem(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn pow(&self, exponent: &Residue, prime: Prime) -> Outcome<Residue> {
+        let (mut result, mut base, mut remaining) = (Residue(1), *self, exponent.0);
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.mul(&base, prime)?;
+            }
+            base = base.mul(&base, prime)?;
+            remaining >>= 1;
+        }
+
+        Ok(result)
+    }
+
+    fn and(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn or(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn xor(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn lsl(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn lsr(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn asl(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn asr(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn rol(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn ror(&self, _: &Residue, _: Prime) -> Outcome<Residue> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn rcl(&self, _: &Residue, _: bool, _: Prime) -> Result<(Residue, bool), ArithmeticError<Residue>> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn rcr(&self, _: &Residue, _: bool, _: Prime) -> Result<(Residue, bool), ArithmeticError<Residue>> {
+        Err(ArithmeticError::Unsupported)
+    }
+
+    fn overflow_policy(_: Prime) -> OverflowPolicy {
+        OverflowPolicy::Error
+    }
+
+    fn saturated(_: bool, _: Prime) -> Residue {
+        unreachable!("residues never overflow")
+    }
+
+    fn format_name(_: Prime) -> &'static str {
+        "modular"
+    }
+
+    fn fmt_value(&self, _: Prime, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+
+    fn bit_pattern(&self, _: Prime) -> (BigInt, u32) {
+        (BigInt::from(self.0 as u128), 64)
+    }
+}
+
+#[test]
+fn test_custom_backend() {
+    let prime = Prime(65521);
+    let run = |input: &str| Parser::<Residue>::with_mode(input.to_string(), prime).parse().map(|value| value.to_string());
+
+    assert_eq!(run("65520 + 2").as_deref(), Ok("1"));
+    assert_eq!(run("1 / 3 * 3").as_deref(), Ok("1"));
+    assert_eq!(run("-1").as_deref(), Ok("65520"));
+    assert_eq!(run("2 ** 65520 == 1 ? max(7, 5) : 0").as_deref(), Ok("7"));
+    assert_eq!(run("1 / 0"), Err(ParseError::DivisionByZero { span: Span::new(2, 3) }));
+    assert_eq!(run("1 xor 2"), Err(ParseError::UnsupportedOperator { operator: "xor".to_string(), format: "modular", span: Span::new(2, 5) }));
+
+    let mut environment = Environment::new();
+    Parser::<Residue>::with_mode("x = 2 ** 16".to_string(), prime).parse_with(&mut environment).unwrap();
+    let result = Parser::<Residue>::with_mode("x - ans + x".to_string(), prime).parse_with(&mut environment).unwrap();
+    assert_eq!(*result.raw(), Residue(15));
+}
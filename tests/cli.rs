@@ -0,0 +1,82 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Runs the binary with the arguments and the standard input, returning the exit code, the
+// standard output and the standard error
+fn run(args: &[&str], stdin: &str) -> (Option<i32>, String, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_programmer_calc_parser"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+
+    let Output { status, stdout, stderr } = child.wait_with_output().unwrap();
+    (status.code(), String::from_utf8(stdout).unwrap(), String::from_utf8(stderr).unwrap())
+}
+
+#[test]
+fn test_cli_arguments() {
+    assert_eq!(run(&["0xFF lsl 4"], ""), (Some(0), "4080\n".to_string(), String::new()));
+    assert_eq!(run(&["x = 6", "x * 7", "-x"], ""), (Some(0), "6\n42\n-6\n".to_string(), String::new()));
+    assert_eq!(run(&["-b", "hex,bin", "--width=8", "-1"], "").1, "hex  0xff\nbin  1111 1111\n");
+    assert_eq!(run(&["-w", "16", "-u", "--base", "udec", "not 0"], "").1, "65535\n");
+    assert_eq!(run(&["--", "--1"], "").1, "1\n");
+
+    let (code, stdout, stderr) = run(&["--width", "12", "1"], "");
+    assert_eq!((code, stdout), (Some(2), String::new()));
+    assert!(stderr.starts_with("Width must be one of 8, 16, 32, 64 or 128\n\nUsage:"));
+    assert_eq!(run(&["--bogus"], "").0, Some(2));
+}
+
+#[test]
+fn test_cli_lines() {
+    let input = "x = 3\n\n# comment\n:width 8\nx * 2\n1 / 0\n127 + 1\n";
+    assert_eq!(run(&[], input), (
+        Some(1),
+        "3\n6\n".to_string(),
+        "<stdin>:6: error: Division by zero\n  | 1 / 0\n  |   ^\n".to_string()
+    ));
+
+    let (code, stdout, stderr) = run(&["--keep-going"], input);
+    assert_eq!((code, stdout.as_str()), (Some(1), "3\n6\n"));
+    assert!(stderr.contains("<stdin>:6: error: Division by zero") && stderr.contains("<stdin>:7: error: Value does not fit in the current width"));
+
+    let path = std::env::temp_dir().join(format!("programmer_calc_parser_cli_{}.txt", std::process::id()));
+    std::fs::write(&path, "1 + 1\r\n:overflow wrap\n0x7fffffff + 1\n").unwrap();
+    let (code, stdout, stderr) = run(&["--file", path.to_str().unwrap(), "-b", "hex"], "ignored\n");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!((code, stdout.as_str()), (Some(0), "0x0000_0002\n0x8000_0000\n"));
+    assert_eq!(stderr, format!("{}:3: (overflow, wrapped)\n", path.display()));
+
+    let (code, _, stderr) = run(&["--file", "/nonexistent/expressions.txt"], "");
+    assert_eq!(code, Some(2));
+    assert!(stderr.starts_with("error: cannot read /nonexistent/expressions.txt"));
+}
+
+#[test]
+fn test_cli_big_integers() {
+    let (code, stdout, stderr) = run(&["--keep-going", ":format big", "2 ** 100000000", ":overflow wrap", "2 ** 100"], "");
+    assert_eq!((code, stdout.as_str()), (Some(1), "1267650600228229401496703205376\n"));
+    assert!(stderr.starts_with("<arg>:2: error: Number takes more than 65536 bits\n"));
+    assert!(stderr.contains("<arg>:3: error: Big integers never overflow, the overflow policy has no effect\n"));
+
+    assert_eq!(run(&["--max-bits", "8", ":format big", "255", "255 + 1"], "").1, "255\n");
+    assert_eq!(run(&["--max-bits=0", "1"], "").0, Some(2));
+}
+
+#[test]
+fn test_cli_json() {
+    let (code, stdout, stderr) = run(&["--json", "-w", "8", "--keep-going", "0x7f + 1", ":overflow wrap", "0x7f + 1", "\"x\"", ":\"x"], "");
+    assert_eq!((code, stderr.as_str()), (Some(1), ""));
+
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, [
+        r#"{"source":"<arg>","line":1,"input":"0x7f + 1","error":{"kind":"overflow","message":"Value does not fit in the current width","span":{"start":5,"end":6}}}"#,
+        r#"{"source":"<arg>","line":3,"input":"0x7f + 1","value":{"bases":{"dec":"-128","sdec":"-128","udec":"128","hex":"0x80","oct":"0o200","bin":"1000 0000"},"width":8,"signed":true,"format":"integer","overflowed":true}}"#,
+        r#"{"source":"<arg>","line":4,"input":"\"x\"","error":{"kind":"unexpected_char","message":"Unexpected character \"\"\"","span":{"start":0,"end":1}}}"#,
+        r#"{"source":"<arg>","line":5,"input":":\"x","error":{"kind":"command","message":"Unknown command: \"x","span":{"start":0,"end":3}}}"#
+    ]);
+}
@@ -0,0 +1,84 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use programmer_calc_parser::{CompiledExpr, Environment, NumMode, OverflowPolicy, ParseError, Parser, Span, Value, Width};
+
+// Counts every allocation of the test binary, which holds a single test so nothing else runs
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_compile_once_evaluate_many() {
+    let mode = NumMode::new(Width::W32, false);
+    let compiled = Parser::new("(field lsr shift and mask) * 2 + max(field, 1, shift) + (field rcl 1) + (0 rcl 1)".to_string(), mode).compile().unwrap();
+    assert_eq!(compiled.variables(), ["field", "shift", "mask"]);
+    assert_send_sync(&compiled);
+
+    let mut environment = Environment::new();
+    environment.set("shift", Value::new(4, mode, false));
+    environment.set("mask", Value::new(0xf, mode, false));
+    environment.set("field", Value::new(0, mode, false));
+
+    let mut total = 0;
+    for field in 0..1000 {
+        environment.set("field", Value::new(field, mode, false));
+        let baseline = ALLOCATIONS.load(Ordering::SeqCst);
+        total += compiled.eval(&environment).unwrap().as_unsigned();
+        assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), baseline);
+    }
+
+    // The carry of the first rotation reaches the second, but not the environment
+    let expected: u128 = (0..1000u128).map(|field| (field >> 4 & 0xf) * 2 + field.max(4) + (field << 1) + u128::from(field >> 31 == 1)).sum();
+    assert_eq!(total, expected);
+    assert!(!environment.carry());
+
+    let shared = &compiled;
+    let environment = &environment;
+    thread::scope(|scope| {
+        let handles: Vec<_> = (0..4).map(|_| scope.spawn(move || shared.eval(environment).map(|value| value.as_unsigned()))).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), Ok(999 / 16 % 16 * 2 + 999 + 1998));
+        }
+    });
+
+    assert_eq!(compiled.eval(&Environment::new()), Err(ParseError::UndefinedVariable { name: "field".to_string(), span: Span::new(1, 6) }));
+    let assignment: Result<CompiledExpr, ParseError> = Parser::new("x = 1".to_string(), mode).compile();
+    assert_eq!(assignment.unwrap_err(), ParseError::CompiledAssignment { name: "x".to_string(), span: Span::new(0, 5) });
+
+    // The native backends call the functions of the integer mode without allocating either
+    let policy = OverflowPolicy::Wrapping;
+    let compiled = Parser::<i32>::with_mode("rotl(field, 4) + max(field, -1, 2) * popcount(field)".to_string(), policy).compile().unwrap();
+    let mut environment = Environment::new();
+    environment.set("field", Value::from_raw(0, policy, false));
+
+    let mut total = 0i32;
+    for field in -500..500 {
+        environment.set("field", Value::from_raw(field, policy, false));
+        let baseline = ALLOCATIONS.load(Ordering::SeqCst);
+        total = total.wrapping_add(*compiled.eval(&environment).unwrap().raw());
+        assert_eq!(ALLOCATIONS.load(Ordering::SeqCst), baseline);
+    }
+
+    let expected = (-500..500i32)
+        .map(|field| field.rotate_left(4).wrapping_add(field.max(2).wrapping_mul(field.count_ones() as i32)))
+        .fold(0i32, i32::wrapping_add);
+    assert_eq!(total, expected);
+}
@@ -0,0 +1,389 @@
+use programmer_calc_parser::{evaluate, format_base, Arity, Associativity, Base, BigMode, Environment, EvalLimits, Expr, LiteralError, NumMode, NumType, Numeric, Operator, OverflowPolicy, ParseError, Parser, PrecedenceTable, Span, SyntaxProfile, Value, Width};
+
+fn parse(input: &str, mode: NumMode) -> Result<String, ParseError> {
+    Parser::new(input.to_string(), mode).parse().map(|value| value.to_string())
+}
+
+#[test]
+fn test_evaluate_precedence() {
+    assert_eq!(evaluate("1 + 2 * 3").unwrap().as_signed(), 7);
+    assert_eq!(evaluate("(1 + 2) * 3").unwrap().as_signed(), 9);
+    assert_eq!(evaluate("1 lsl 4 + 1").unwrap().as_signed(), 17);
+    assert_eq!(evaluate("0xf0 or 0x0f and 0x3c").unwrap().as_signed(), 0xfc);
+    assert_eq!(evaluate("-(2 + 3)").unwrap().as_signed(), -5);
+    assert_eq!(evaluate("2(3 + 1)").unwrap().as_signed(), 8);
+}
+
+#[test]
+fn test_evaluate_literals() {
+    assert_eq!(evaluate("0xFF and 0b1010").unwrap().as_signed(), 10);
+    assert_eq!(evaluate("0o755").unwrap().as_signed(), 493);
+    assert_eq!(evaluate("0FFh + 1").unwrap().as_signed(), 256);
+    assert_eq!(evaluate("0x7fff_ffff").unwrap().as_signed(), i32::MAX as i128);
+}
+
+#[test]
+fn test_parse_width_and_signedness() {
+    let byte = NumMode::new(Width::W8, false);
+    assert_eq!(parse("not 0", byte).as_deref(), Ok("255"));
+    assert_eq!(parse("0x80 lsr 1", byte).as_deref(), Ok("64"));
+    assert_eq!(parse("0x80 asr 1", byte).as_deref(), Ok("192"));
+
+    let signed_byte = NumMode::new(Width::W8, true);
+    assert_eq!(parse("not 0", signed_byte).as_deref(), Ok("-1"));
+    assert_eq!(parse("0x80 lsr 1", signed_byte).as_deref(), Ok("64"));
+    assert_eq!(parse("0x80 asr 1", signed_byte).as_deref(), Ok("-64"));
+}
+
+#[test]
+fn test_parse_signed_decimal_range() {
+    for (width, min, max) in [
+        (Width::W8, i8::MIN as i128, i8::MAX as i128),
+        (Width::W16, i16::MIN as i128, i16::MAX as i128),
+        (Width::W32, i32::MIN as i128, i32::MAX as i128),
+        (Width::W64, i64::MIN as i128, i64::MAX as i128),
+        (Width::W128, i128::MIN, i128::MAX)
+    ] {
+        let mode = NumMode::new(width, true);
+        let max_plus_one = (max as u128 + 1).to_string();
+
+        assert_eq!(parse(&min.to_string(), mode), Ok(min.to_string()));
+        assert_eq!(parse(&max.to_string(), mode), Ok(max.to_string()));
+        assert_eq!(parse(&max_plus_one, mode), Err(ParseError::Overflow { span: Span::new(0, max_plus_one.len()) }));
+        assert_eq!(parse(&format!("-{}", max_plus_one), mode), Ok(min.to_string()));
+        assert_eq!(parse(&format!("-{} - 1", max_plus_one), mode.with_overflow_policy(OverflowPolicy::Wrapping)), Ok(max.to_string()));
+    }
+
+    // Prefixed literals stay bit patterns, and the minus of a power applies to the power
+    let byte = NumMode::new(Width::W8, true);
+    assert_eq!(parse("0xff", byte).as_deref(), Ok("-1"));
+    assert_eq!(parse("200", byte), Err(ParseError::Overflow { span: Span::new(0, 3) }));
+    assert_eq!(parse("-129", byte), Err(ParseError::Overflow { span: Span::new(1, 4) }));
+    assert_eq!(parse("-2 ** 2", byte).as_deref(), Ok("-4"));
+    assert_eq!(parse("-1", NumMode::new(Width::W8, false).with_overflow_policy(OverflowPolicy::Wrapping)).as_deref(), Ok("255"));
+}
+
+#[test]
+fn test_parse_overflow_policies() {
+    let mode = NumMode::default();
+    assert_eq!(parse("2147483647 + 1", mode), Err(ParseError::Overflow { span: Span::new(11, 12) }));
+    assert_eq!(parse("2147483647 + 1", mode.with_overflow_policy(OverflowPolicy::Wrapping)).as_deref(), Ok("-2147483648"));
+    assert_eq!(parse("2147483647 + 1", mode.with_overflow_policy(OverflowPolicy::Saturating)).as_deref(), Ok("2147483647"));
+
+    let value = Parser::new("2147483647 + 1".to_string(), mode.with_overflow_policy(OverflowPolicy::Wrapping)).parse().unwrap();
+    assert!(value.overflowed());
+}
+
+#[test]
+fn test_evaluate_errors() {
+    assert_eq!(evaluate("1 / 0"), Err(ParseError::DivisionByZero { span: Span::new(2, 3) }));
+    assert_eq!(evaluate("1 asl 40"), Err(ParseError::InvalidShift { span: Span::new(2, 5) }));
+    assert_eq!(evaluate("1 + $"), Err(ParseError::UnexpectedChar { found: '$', span: Span::new(4, 5) }));
+    assert_eq!(evaluate("(1 + 2"), Err(ParseError::UnmatchedParen { span: Span::new(0, 1) }));
+    assert!(matches!(evaluate("1 lsx 2"), Err(ParseError::UnknownWord { .. })));
+}
+
+#[test]
+fn test_evaluate_with_environment() {
+    let mut environment = Environment::new();
+    let mut parse = |input: &str| Parser::new(input.to_string(), NumMode::default()).parse_with(&mut environment).map(|value| value.to_string());
+
+    assert_eq!(parse("mask = 0xFF00").as_deref(), Ok("65280"));
+    assert_eq!(parse("reg = 0x1234").as_deref(), Ok("4660"));
+    assert_eq!(parse("reg and mask").as_deref(), Ok("4608"));
+    assert_eq!(parse("ans lsr 8").as_deref(), Ok("18"));
+    assert_eq!(parse("_ + 1").as_deref(), Ok("19"));
+    assert_eq!(parse("x + 1"), Err(ParseError::UndefinedVariable { name: "x".to_string(), span: Span::new(0, 1) }));
+    assert_eq!(parse("ans").as_deref(), Ok("19"));
+
+    assert_eq!(environment.get("reg").map(|value| value.as_unsigned()), Some(0x1234));
+}
+
+#[test]
+fn test_evaluate_functions() {
+    let byte = NumMode::new(Width::W8, false);
+
+    assert_eq!(parse("popcount(0xff00) + clz(1)", NumMode::default()).as_deref(), Ok("39"));
+    assert_eq!(parse("bswap(0x1234)", NumMode::new(Width::W16, false)).as_deref(), Ok("13330"));
+    assert_eq!(parse("rotl(0x81, 1) or mask(4, 5)", byte).as_deref(), Ok("51"));
+    assert_eq!(parse("max(3, -7, 2 * 2) + abs(-7)", NumMode::default()).as_deref(), Ok("11"));
+    assert_eq!(parse("sext(0xf, 4)", NumMode::new(Width::W8, true)).as_deref(), Ok("-1"));
+    assert_eq!(parse("sign_extend(0x80, 8)", NumMode::new(Width::W16, true)).as_deref(), Ok("-128"));
+
+    assert_eq!(parse("bit(8)", byte), Err(ParseError::InvalidArgument {
+        reason: "Bit index must be between 0 and the width minus one",
+        span: Span::new(4, 5)
+    }));
+    assert_eq!(evaluate("popcnt(1)"), Err(ParseError::UnknownFunction { name: "popcnt".to_string(), span: Span::new(0, 6) }));
+    assert_eq!(evaluate("rotl(1)"), Err(ParseError::ArgumentCount {
+        name: "rotl".to_string(),
+        expected: Arity::Exactly(2),
+        found: 1,
+        span: Span::new(0, 7)
+    }));
+    assert_eq!(evaluate("abs(-2147483647 - 1)"), Err(ParseError::Overflow { span: Span::new(0, 20) }));
+}
+
+#[test]
+fn test_evaluate_c_symbols() {
+    assert_eq!(evaluate("~0 << 4 & 0xff | 1 ^ 3"), evaluate("not 0 lsl 4 and 0xff or 1 xor 3"));
+    assert_eq!(evaluate("-16 >> 2").map(|value| value.as_signed()), Ok(-4));
+    assert_eq!(evaluate("-16 >>> 28").map(|value| value.as_signed()), Ok(15));
+
+    let parse = |input: &str, syntax: SyntaxProfile| Parser::new(input.to_string(), NumMode::default()).with_syntax(syntax).parse();
+    assert_eq!(parse("6 & 3", SyntaxProfile::CLike).map(|value| value.as_signed()), Ok(2));
+    assert_eq!(parse("6 and 3", SyntaxProfile::CLike), Err(ParseError::UnknownWord { found: "and".to_string(), span: Span::new(2, 5) }));
+    assert_eq!(parse("6 & 3", SyntaxProfile::Keywords), Err(ParseError::UnexpectedChar { found: '&', span: Span::new(2, 3) }));
+}
+
+#[test]
+fn test_evaluate_precedence_presets() {
+    let parse = |input: &str, precedence: PrecedenceTable| Parser::new(input.to_string(), NumMode::default()).with_precedence(precedence).parse().map(|value| value.to_string());
+
+    assert_eq!(parse("1 << 2 + 3", PrecedenceTable::default()).as_deref(), Ok("7"));
+    assert_eq!(parse("1 << 2 + 3", PrecedenceTable::c()).as_deref(), Ok("32"));
+    assert_eq!(parse("10 - 4 - 3", PrecedenceTable::python().with(Operator::Sub, 8, Associativity::Right)).as_deref(), Ok("9"));
+}
+
+#[test]
+fn test_evaluate_power() {
+    assert_eq!(evaluate("2 ** 16").map(|value| value.as_signed()), Ok(65536));
+    assert_eq!(evaluate("2 ** 3 ** 2").map(|value| value.as_signed()), Ok(512));
+    assert_eq!(evaluate("-2 ** 2").map(|value| value.as_signed()), Ok(-4));
+    assert_eq!(evaluate("(-2) ** 3 * pow(3, 2)").map(|value| value.as_signed()), Ok(-72));
+    assert_eq!(evaluate("2 ** 31"), Err(ParseError::Overflow { span: Span::new(2, 4) }));
+    assert_eq!(evaluate("2 ** -1"), Err(ParseError::NegativeExponent { span: Span::new(2, 4) }));
+    assert_eq!(parse("2 ** 31", NumMode::new(Width::W32, false)).as_deref(), Ok("2147483648"));
+}
+
+#[test]
+fn test_evaluate_comparisons() {
+    let mut environment = Environment::new();
+    let mut run = |input: &str| Parser::new(input.to_string(), NumMode::default()).parse_with(&mut environment).map(|value| value.as_signed());
+
+    assert_eq!(run("x = 0x13"), Ok(19));
+    assert_eq!(run("(x and 0xF) == 3"), Ok(1));
+    assert_eq!(run("x and 0xf == 3"), Ok(1));
+    assert_eq!(run("-1 < 1 && x != 0 && !(x >= 20)"), Ok(1));
+    assert_eq!(run("x > 100 || x <= 0"), Ok(0));
+    assert_eq!(run("x > 16 ? x - 16 : x"), Ok(3));
+    assert_eq!(run("x == 0 ? 1 / x : -1"), Ok(-1));
+    assert_eq!(run("1 ? 2"), Err(ParseError::MissingColon { span: Span::new(2, 3) }));
+
+    assert_eq!(parse("0xffffffff < 1", NumMode::default()).as_deref(), Ok("1"));
+    assert_eq!(parse("0xffffffff < 1", NumMode::new(Width::W32, false)).as_deref(), Ok("0"));
+}
+
+#[test]
+fn test_evaluate_rotations() {
+    let byte = NumMode::new(Width::W8, false);
+
+    assert_eq!(parse("0x81 rol 1", byte).as_deref(), Ok("3"));
+    assert_eq!(parse("0x81 ror 1 + 1", byte).as_deref(), Ok("193"));
+    assert_eq!(parse("0x12345678 rol 8", NumMode::new(Width::W32, false)).as_deref(), Ok("878082066"));
+
+    let mut environment = Environment::new();
+    environment.set_carry(true);
+    let mut run = |input: &str| Parser::new(input.to_string(), byte).parse_with(&mut environment).map(|value| value.to_string());
+
+    assert_eq!(run("0x80 rcl 1").as_deref(), Ok("1"));
+    assert_eq!(run("0 rcr 1").as_deref(), Ok("128"));
+    assert!(!environment.carry());
+
+    // A negative amount rotates the other way, in every backend
+    let signed_byte = NumMode::new(Width::W8, true);
+    for (input, expected, carry) in [("1 rcl -1", "0", true), ("1 rcr -1", "2", false), ("-128 rcl -10", "64", false), ("1 rcr -9", "1", false)] {
+        let mut environment = Environment::new();
+        let value = Parser::new(input.to_string(), signed_byte).parse_with(&mut environment).unwrap();
+        assert_eq!((value.to_string(), environment.carry()), (expected.to_string(), carry), "{}", input);
+
+        let mut environment = Environment::new();
+        let value = Parser::big(input.to_string(), BigMode::new(8, true).unwrap()).parse_with(&mut environment).unwrap();
+        assert_eq!((value.to_string(), environment.carry()), (expected.to_string(), carry), "{}", input);
+    }
+}
+
+#[test]
+fn test_evaluate_float_mode() {
+    let float = NumMode::float();
+
+    assert_eq!(parse("1.5 * 4 - 2e-1", float).as_deref(), Ok("5.8"));
+    assert_eq!(parse("1 / 4 + 0x10", float).as_deref(), Ok("16.25"));
+    assert_eq!(parse("2 ** -1 + 7 % 2.5", float).as_deref(), Ok("2.5"));
+    assert_eq!(parse("1e20 * 1e10", float).as_deref(), Ok("1e30"));
+    assert_eq!(parse("0.1 + 0.2 > 0.3 ? 1 : 0", float).as_deref(), Ok("1"));
+    assert_eq!(parse("max(-1.5, abs(-2.25))", float).as_deref(), Ok("2.25"));
+    assert_eq!(parse("1e300 * 1e10", float), Err(ParseError::Overflow { span: Span::new(6, 7) }));
+    assert_eq!(parse("1e300 * 1e10", float.with_overflow_policy(OverflowPolicy::Saturating)).as_deref(), Ok("1.7976931348623157e308"));
+    assert_eq!(parse("1 / 0.0", float), Err(ParseError::DivisionByZero { span: Span::new(2, 3) }));
+    assert_eq!(parse("(-8) ** 0.5", float), Err(ParseError::FractionalExponent { span: Span::new(5, 7) }));
+
+    assert_eq!(parse("3 and 1", float), Err(ParseError::UnsupportedOperator {
+        operator: "and".to_string(),
+        format: "float",
+        span: Span::new(2, 5)
+    }));
+    assert!(matches!(parse("popcount(3)", float), Err(ParseError::UnsupportedFunction { .. })));
+    assert_eq!(parse("1.5", NumMode::default()), Err(ParseError::InvalidNumber { span: Span::new(0, 3) }));
+    assert_eq!(<NumType as Numeric>::parse("1g", 16, float), Err(LiteralError::Invalid));
+    assert_eq!(<NumType as Numeric>::parse("1g", 16, NumMode::from_q_notation("q15").unwrap()), Err(LiteralError::Invalid));
+    assert_eq!(<NumType as Numeric>::parse(&"f".repeat(33), 16, float), Err(LiteralError::Overflow));
+}
+
+#[test]
+fn test_evaluate_fixed_point_mode() {
+    let q15 = NumMode::from_q_notation("q15").unwrap();
+    let q16_16 = NumMode::from_q_notation("q16.16").unwrap();
+
+    assert_eq!(q15, NumMode::from_q_notation("q1.15").unwrap());
+    assert_eq!(parse("0.5 * 0.5 + 0.125", q15).as_deref(), Ok("0.375"));
+    assert_eq!(parse("-0.75 / 2 * 2", q16_16).as_deref(), Ok("-0.75"));
+    assert_eq!(parse("0x4000 == 0.5", q15).as_deref(), Ok("0.999969482421875"));
+    assert_eq!(parse("1", q15), Err(ParseError::Overflow { span: Span::new(0, 1) }));
+    assert_eq!(parse("-1", q15).as_deref(), Ok("-1"));
+    assert_eq!(parse("-1 == 0x8000", q15).as_deref(), Ok("0.999969482421875"));
+    assert_eq!(parse("-1.5", q15), Err(ParseError::Overflow { span: Span::new(1, 4) }));
+    assert_eq!(parse("0.75 + 0.5", q15.with_overflow_policy(OverflowPolicy::Saturating)).as_deref(), Ok("0.999969482421875"));
+
+    // Bitwise operators work on the raw bits, shift amounts are whole numbers
+    assert_eq!(parse("3.75 and 0xffff0000", q16_16).as_deref(), Ok("3"));
+    assert_eq!(parse("10 >> 2", q16_16).as_deref(), Ok("2.5"));
+    assert_eq!(parse("10 >> 1.5", q16_16), Err(ParseError::InvalidShift { span: Span::new(3, 5) }));
+    assert!(matches!(parse("2 ** 2", q16_16), Err(ParseError::UnsupportedOperator { .. })));
+
+    let mut environment = Environment::new();
+    Parser::new("x = -2.75".to_string(), q16_16).parse_with(&mut environment).unwrap();
+    let x = Parser::new("x * 2".to_string(), NumMode::default()).parse_with(&mut environment).unwrap();
+    assert_eq!(x.as_signed(), -4);
+}
+
+#[test]
+fn test_evaluate_big_integers() {
+    let big = |input: &str, mode: BigMode| Parser::big(input.to_string(), mode).parse().map(|value| value.to_string());
+    let i256 = BigMode::default();
+    assert_eq!(BigMode::new(0, true), None);
+
+    assert_eq!(big("2 ** 256 - 1", i256).as_deref(), Ok("115792089237316195423570985008687907853269984665640564039457584007913129639935"));
+    assert_eq!(big("not 0", BigMode::new(256, false).unwrap()), big("2 ** 256 - 1", i256));
+    assert_eq!(big("0xffffffffffffffffffffffffffffffffff + 1", i256).as_deref(), Ok("87112285931760246646623899502532662132736"));
+    assert_eq!(big("-(2 ** 200) / 3 * 3 + 2 ** 200 % 3", i256).as_deref(), Ok("-1606938044258990275541962092341162602522202993782792835301374"));
+
+    // Two's complement operators work on the declared width
+    assert_eq!(big("not 0", BigMode::new(8, true).unwrap()).as_deref(), Ok("-1"));
+    assert_eq!(big("not 0", BigMode::new(8, false).unwrap()).as_deref(), Ok("255"));
+    assert_eq!(big("-1 lsr 252", i256).as_deref(), Ok("15"));
+    assert_eq!(big("-9 asr 1 and 0xff", i256).as_deref(), Ok("251"));
+    assert_eq!(big("0x81 rol 1", BigMode::new(8, false).unwrap()).as_deref(), Ok("3"));
+    assert_eq!(big("1 lsr 256", i256), Err(ParseError::InvalidShift { span: Span::new(2, 5) }));
+    assert_eq!(big("1 / 0", i256), Err(ParseError::DivisionByZero { span: Span::new(2, 3) }));
+    assert_eq!(big("abs(min(-(2 ** 100), 1))", i256), big("pow(2, 100)", i256));
+
+    let value = Parser::big("-1".to_string(), BigMode::new(8, true).unwrap()).parse().unwrap();
+    assert_eq!(format_base(&value, Base::Hex), "0xff");
+    let value = Parser::big("2 ** 8".to_string(), BigMode::new(8, false).unwrap()).parse().unwrap();
+    assert_eq!(format_base(&value, Base::Hex), "0x0100");
+}
+
+#[test]
+fn test_evaluate_native_backends() {
+    let run = |input: &str| Parser::<i8>::with_mode(input.to_string(), OverflowPolicy::Error).parse().map(|value| value.to_string());
+    assert_eq!(run("0x7f + 1"), Err(ParseError::Overflow { span: Span::new(5, 6) }));
+    assert_eq!(run("0xff asr 4 + popcount(0xf0)").as_deref(), Ok("3"));
+    assert_eq!(run("(-127 - 1) / -1"), Err(ParseError::Overflow { span: Span::new(11, 12) }));
+
+    let wrapped = Parser::<u16>::with_mode("0 - 1".to_string(), OverflowPolicy::Wrapping).parse().unwrap();
+    assert_eq!((*wrapped.raw(), wrapped.overflowed()), (u16::MAX, true));
+    assert_eq!(format_base(&wrapped, Base::Hex), "0xffff");
+
+    let saturated = Parser::<i64>::with_mode("2 ** 70 + min(3, -4)".to_string(), OverflowPolicy::Saturating).parse().unwrap();
+    assert_eq!((*saturated.raw(), saturated.overflowed()), (i64::MAX - 4, true));
+
+    let float = |input: &str| Parser::<f64>::with_mode(input.to_string(), OverflowPolicy::Error).parse().map(|value| value.to_string());
+    assert_eq!(float("1.5 * 0x10 + abs(-0.25)").as_deref(), Ok("24.25"));
+    assert!(matches!(float("1 lsl 2"), Err(ParseError::UnsupportedOperator { format: "f64", .. })));
+    assert!(matches!(float("clz(1)"), Err(ParseError::UnsupportedFunction { format: "f64", .. })));
+}
+
+#[test]
+fn test_evaluate_long_flat_expressions() {
+    let input = format!("0{}", " + 1".repeat(100_000));
+    let parser = Parser::new(input, NumMode::new(Width::W64, true));
+
+    assert_eq!(parser.parse().map(|value| value.as_signed()), Ok(100_000));
+    assert_eq!(parser.compile().unwrap().eval(&Environment::new()).map(|value| value.as_signed()), Ok(100_000));
+
+    // The tree of a long run of operators is as flat as the run, to copy, compare and print
+    let input = format!("x{}", " - 1".repeat(200_000));
+    let expression = Parser::new(input.clone(), NumMode::default()).analyze().unwrap();
+    let copy = expression.clone();
+    assert_eq!(copy, expression);
+    assert_eq!(expression.to_string(), input);
+    assert!(format!("{:?}", copy).len() > input.len());
+
+    let Expr::Binary { first, links, .. } = expression else { panic!("not a binary expression") };
+    assert_eq!((*first, links.len()), (Expr::Variable { name: "x".to_string(), span: Span::new(0, 1) }, 200_000));
+}
+
+#[test]
+fn test_evaluate_deeply_nested_expressions() {
+    let depth = 100_000;
+    let inputs = [
+        format!("{}1{}", "(".repeat(depth), ")".repeat(depth)),
+        "(".repeat(depth),
+        format!("{}1", "-".repeat(depth)),
+        format!("{}1", "+".repeat(depth)),
+        format!("1{}", " ** 1".repeat(depth)),
+        format!("1{}", " ? 1".repeat(depth)),
+        format!("{}1{}", "abs(".repeat(depth), ")".repeat(depth)),
+        format!("{}1", "a = ".repeat(depth)),
+        format!("{}1{}", "1 || 1 && 1 | 1 ^ 1 & 1 == 1 < 1 << 1 + 1 * 1 ** -(".repeat(depth / 10), ")".repeat(depth / 10))
+    ];
+
+    for input in inputs {
+        let parser = Parser::new(input, NumMode::new(Width::W64, true));
+        assert!(matches!(parser.parse(), Err(ParseError::NestingTooDeep { limit: 128, .. })));
+    }
+
+    let input = format!("{}1{}", "(".repeat(150), ")".repeat(150));
+    let parser = Parser::new(input, NumMode::new(Width::W64, true));
+    assert!(matches!(parser.parse(), Err(ParseError::NestingTooDeep { limit: 128, span }) if span == Span::new(128, 129)));
+    assert_eq!(parser.with_limits(EvalLimits::default().with_max_depth(150)).parse().map(|value| value.as_signed()), Ok(1));
+}
+
+#[test]
+fn test_evaluate_limits() {
+    let limited = |input: &str, limits: EvalLimits| Parser::new(input.to_string(), NumMode::new(Width::W64, true)).with_limits(limits).parse().map(|value| value.as_signed());
+
+    assert_eq!(limited("1 + 2 + 3 + 4", EvalLimits::default().with_max_input_length(8)), Err(ParseError::InputTooLong { limit: 8, span: Span::new(8, 13) }));
+    assert_eq!(limited("1 + 2 + 3 + 4", EvalLimits::default().with_max_tokens(3)), Err(ParseError::TooManyTokens { limit: 3, span: Span::new(6, 7) }));
+    assert_eq!(limited("((1))", EvalLimits::default().with_max_depth(2)), Ok(1));
+    assert_eq!(limited("(((1)))", EvalLimits::default().with_max_depth(2)), Err(ParseError::NestingTooDeep { limit: 2, span: Span::new(2, 3) }));
+    assert_eq!(limited("1 + 2 + 3 + 4", EvalLimits::default().with_max_operations(2)), Err(ParseError::TooManyOperations { limit: 2, span: Span::new(10, 11) }));
+    assert_eq!(limited("abs(abs(1))", EvalLimits::default().with_max_call_depth(2)), Ok(1));
+    assert_eq!(limited("abs(abs(abs(1)))", EvalLimits::default().with_max_call_depth(2)), Err(ParseError::CallsTooDeep { limit: 2, span: Span::new(8, 11) }));
+
+    // Every evaluation of a compiled expression gets the whole budget
+    let parser = Parser::new("x * 2 + 1".to_string(), NumMode::default()).with_limits(EvalLimits::default().with_max_operations(2));
+    let compiled = parser.compile().unwrap();
+    let mut environment = Environment::new();
+    environment.set("x", Value::new(20, NumMode::default(), false));
+    assert_eq!(compiled.eval(&environment).map(|value| value.as_signed()), Ok(41));
+    assert_eq!(compiled.eval(&environment).map(|value| value.as_signed()), Ok(41));
+
+    // Fixed widths never grow, big integers are stopped before computing what is sure to be too large
+    let big = |input: &str| Parser::big(input.to_string(), BigMode::default()).with_limits(EvalLimits::untrusted().with_max_big_int_bits(64)).parse().map(|value| value.to_string());
+    assert_eq!(limited("0x7fffffffffffffff * 1", EvalLimits::untrusted().with_max_big_int_bits(8)), Ok(i64::MAX as i128));
+    assert_eq!(big("2 ** 63").as_deref(), Ok("9223372036854775808"));
+    assert_eq!(big("3 ** 40").as_deref(), Ok("12157665459056928801"));
+    assert_eq!(big("2 ** 64"), Err(ParseError::NumberTooLarge { limit: 64, span: Span::new(2, 4) }));
+    assert_eq!(big("3 ** 41"), Err(ParseError::NumberTooLarge { limit: 64, span: Span::new(2, 4) }));
+    assert_eq!(big("2 ** 1000000000"), Err(ParseError::NumberTooLarge { limit: 64, span: Span::new(2, 4) }));
+    assert_eq!(big("1 lsl 4000000000"), Err(ParseError::NumberTooLarge { limit: 64, span: Span::new(2, 5) }));
+
+    // Shifting zero grows nothing, even with the default limit
+    let untrusted = |input: &str| Parser::big(input.to_string(), BigMode::default()).with_limits(EvalLimits::untrusted()).parse().map(|value| value.to_string());
+    assert_eq!(untrusted("0 lsl 4000000000").as_deref(), Ok("0"));
+    assert_eq!(untrusted("0 asl 4000000000").as_deref(), Ok("0"));
+    assert_eq!(big("pow(2, 64)"), Err(ParseError::NumberTooLarge { limit: 64, span: Span::new(0, 10) }));
+    assert_eq!(big("0x10000000000000000"), Err(ParseError::NumberTooLarge { limit: 64, span: Span::new(0, 19) }));
+}